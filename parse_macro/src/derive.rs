@@ -0,0 +1,373 @@
+use std::collections::{HashMap, HashSet};
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_quote,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Attribute, Data, DataEnum, DeriveInput, Fields, Lit, LitStr, Meta, NestedMeta, Token,
+};
+
+use crate::{
+    extract_struct_fields, generate_command_parse_impl, generate_display_impl,
+    parse_tree::{ParseNode, ParseTree},
+    with_command_parse_bounds, AttributeData, CodegenConfig, Errors, ParseAttr,
+};
+
+pub(crate) fn handle_derive(input: DeriveInput) -> syn::Result<TokenStream> {
+    let config = CommandAttr::from_attributes(&input.attrs)?;
+    if let Some(rename) = &config.rename {
+        return Err(syn::Error::new(
+            rename.span(),
+            "`rename` is only allowed on variants",
+        ));
+    }
+
+    match &input.data {
+        Data::Enum(data) => derive_enum(&input, data, &config),
+        Data::Struct(data) => derive_struct(&input, &data.fields, config),
+        Data::Union(_) => Err(syn::Error::new(
+            input.ident.span(),
+            "CommandParse can only be derived for enums and structs",
+        )),
+    }
+}
+
+/// Derives `CommandParse` and `Display` for an enum of unit variants, each of which is parsed from a keyword
+fn derive_enum(
+    input: &DeriveInput,
+    data: &DataEnum,
+    config: &CommandAttr,
+) -> syn::Result<TokenStream> {
+    if let Some(template) = config.templates.first() {
+        return Err(syn::Error::new(
+            template.parse_template.span(),
+            "Templates are only allowed on structs",
+        ));
+    }
+
+    let case = match &config.rename_all {
+        Some(rename_all) => Some(Case::from_lit(rename_all)?),
+        None => None,
+    };
+
+    let mut parse_tree = ParseTree::new();
+    let mut keywords: Vec<String> = Vec::new();
+    let mut variants = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new(
+                variant.fields.span(),
+                "CommandParse can only be derived for enums without fields",
+            ));
+        }
+
+        let variant_config = CommandAttr::from_attributes(&variant.attrs)?;
+        if let Some(rename_all) = &variant_config.rename_all {
+            return Err(syn::Error::new(
+                rename_all.span(),
+                "`rename_all` is only allowed on the enum",
+            ));
+        }
+        let keyword = match &variant_config.rename {
+            Some(rename) => rename.value(),
+            None => {
+                let name = variant.ident.to_string();
+                match case {
+                    Some(case) => case.apply(&name),
+                    None => name,
+                }
+            }
+        };
+        let keyword = keyword
+            .split_ascii_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if keyword.is_empty() {
+            return Err(syn::Error::new(
+                variant.span(),
+                "The keyword must not be empty",
+            ));
+        }
+        if keywords.contains(&keyword) {
+            return Err(syn::Error::new(
+                variant.span(),
+                format!("The keyword '{}' is used by multiple variants", keyword),
+            ));
+        }
+
+        let ident = &variant.ident;
+        parse_tree.insert(
+            keyword
+                .split(' ')
+                .map(|part| ParseNode::Literal(part.to_string())),
+            parse_quote!(Self::#ident),
+            HashMap::new(),
+            Vec::new(),
+        );
+        keywords.push(keyword);
+        variants.push(ident);
+    }
+
+    let name = &input.ident;
+    let codegen = CodegenConfig::default();
+    let generics = with_command_parse_bounds(&input.generics, &codegen.krate);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let command_parse_impl =
+        generate_command_parse_impl(&parse_tree, name, &generics, &[], None, &codegen);
+    Ok(quote! {
+        impl #impl_generics ::std::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                match *self {#(
+                    Self::#variants => f.write_str(#keywords),
+                )*}
+            }
+        }
+
+        #command_parse_impl
+    })
+}
+
+/// Derives `CommandParse` and `Display` for a struct from its `#[command("...")]` templates
+fn derive_struct(
+    input: &DeriveInput,
+    fields: &Fields,
+    config: CommandAttr,
+) -> syn::Result<TokenStream> {
+    if let Some(rename_all) = &config.rename_all {
+        return Err(syn::Error::new(
+            rename_all.span(),
+            "`rename_all` is only allowed on enums",
+        ));
+    }
+    if config.templates.is_empty() {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "Expected a template like `#[command(\"$field\")]`",
+        ));
+    }
+
+    let fields = extract_struct_fields(fields)?;
+    let mut parse_tree = ParseTree::new();
+    let mut parse_attrs = Vec::new();
+    let mut optional_args = HashSet::new();
+    let mut errors = Errors::default();
+    for attr_data in config.templates {
+        let parse_attr = match errors.collect(ParseAttr::new(attr_data, &fields)) {
+            Some(parse_attr) => parse_attr,
+            None => continue,
+        };
+        parse_tree.insert(
+            parse_attr.parse_template.iter().cloned(),
+            parse_quote!(Self),
+            parse_attr.values(),
+            fields.keys().cloned().collect(),
+        );
+        optional_args.extend(parse_attr.kwargs.keys().cloned());
+        parse_attrs.push(parse_attr);
+    }
+    errors.finish()?;
+
+    let name = &input.ident;
+    let codegen = CodegenConfig::default();
+    let display_impl = generate_display_impl(
+        optional_args.into_iter().collect(),
+        &parse_attrs,
+        name,
+        &input.generics,
+        &codegen,
+    );
+    let generics = with_command_parse_bounds(&input.generics, &codegen.krate);
+    let command_parse_impl =
+        generate_command_parse_impl(&parse_tree, name, &generics, &[], None, &codegen);
+    Ok(quote! {
+        #display_impl
+
+        #command_parse_impl
+    })
+}
+
+/// The arguments of all `#[command(...)]` attributes on an item
+#[derive(Default)]
+struct CommandAttr {
+    rename: Option<LitStr>,
+    rename_all: Option<LitStr>,
+    /// Parse templates like `#[command("$player $scoreboard")]`
+    templates: Vec<AttributeData>,
+}
+
+impl CommandAttr {
+    fn from_attributes(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut config = CommandAttr::default();
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("command")) {
+            let is_template = attr
+                .parse_args_with(|input: ParseStream| {
+                    let is_template = input.peek(LitStr);
+                    input.parse::<TokenStream>()?;
+                    Ok(is_template)
+                })
+                .unwrap_or(false);
+            if is_template {
+                config
+                    .templates
+                    .push(attr.parse_args_with(AttributeData::parse)?);
+                continue;
+            }
+
+            let args =
+                attr.parse_args_with(Punctuated::<NestedMeta, Token![,]>::parse_terminated)?;
+            for arg in args {
+                let (target, value) = match &arg {
+                    NestedMeta::Meta(Meta::NameValue(name_value)) => {
+                        let target = if name_value.path.is_ident("rename") {
+                            &mut config.rename
+                        } else if name_value.path.is_ident("rename_all") {
+                            &mut config.rename_all
+                        } else {
+                            return Err(syn::Error::new(arg.span(), "Unknown argument"));
+                        };
+                        match &name_value.lit {
+                            Lit::Str(value) => (target, value.clone()),
+                            other => {
+                                return Err(syn::Error::new(other.span(), "Expected a string"))
+                            }
+                        }
+                    }
+                    _ => return Err(syn::Error::new(arg.span(), "Unknown argument")),
+                };
+                if target.is_some() {
+                    return Err(syn::Error::new(arg.span(), "Duplicate argument"));
+                }
+                *target = Some(value);
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// A case style for `rename_all`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Case {
+    Lower,
+    Upper,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    Camel,
+    Pascal,
+}
+
+impl Case {
+    fn from_lit(lit: &LitStr) -> syn::Result<Self> {
+        let case = match lit.value().as_str() {
+            "lowercase" => Case::Lower,
+            "UPPERCASE" => Case::Upper,
+            "snake_case" => Case::Snake,
+            "SCREAMING_SNAKE_CASE" => Case::ScreamingSnake,
+            "kebab-case" => Case::Kebab,
+            "camelCase" => Case::Camel,
+            "PascalCase" => Case::Pascal,
+            _ => return Err(syn::Error::new(
+                lit.span(),
+                "Expected one of \"lowercase\", \"UPPERCASE\", \"snake_case\", \"SCREAMING_SNAKE_CASE\", \"kebab-case\", \"camelCase\" or \"PascalCase\"",
+            )),
+        };
+        Ok(case)
+    }
+
+    /// Converts a name in `PascalCase`, like most variant names, to this case
+    pub(crate) fn apply(self, name: &str) -> String {
+        let words = split_words(name);
+        let capitalize = |word: &str| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first
+                    .to_uppercase()
+                    .chain(chars.flat_map(char::to_lowercase))
+                    .collect(),
+                None => String::new(),
+            }
+        };
+
+        match self {
+            Case::Lower => words.concat().to_lowercase(),
+            Case::Upper => words.concat().to_uppercase(),
+            Case::Snake => words.join("_").to_lowercase(),
+            Case::ScreamingSnake => words.join("_").to_uppercase(),
+            Case::Kebab => words.join("-").to_lowercase(),
+            Case::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(index, word)| {
+                    if index == 0 {
+                        word.to_lowercase()
+                    } else {
+                        capitalize(word)
+                    }
+                })
+                .collect(),
+            Case::Pascal => words.iter().map(|word| capitalize(word)).collect(),
+        }
+    }
+}
+
+/// Splits a name like `MotionBlockingNoLeaves` or `HTTPServer` into its words
+fn split_words(name: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = name.char_indices().collect();
+    let mut words = Vec::new();
+    let mut start = 0;
+    for (position, (index, c)) in chars.iter().enumerate() {
+        if *c == '_' {
+            if start < *index {
+                words.push(&name[start..*index]);
+            }
+            start = index + 1;
+            continue;
+        }
+
+        let previous = position.checked_sub(1).map(|position| chars[position].1);
+        let next = chars.get(position + 1).map(|(_, c)| *c);
+        let is_boundary = c.is_uppercase()
+            && start < *index
+            && (previous.map_or(false, |c| c.is_lowercase() || c.is_ascii_digit())
+                || next.map_or(false, char::is_lowercase));
+        if is_boundary {
+            words.push(&name[start..*index]);
+            start = *index;
+        }
+    }
+    if start < name.len() {
+        words.push(&name[start..]);
+    }
+    words
+}
+
+#[cfg(test)]
+mod test {
+    use super::{split_words, Case};
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(split_words("MotionBlocking"), vec!["Motion", "Blocking"]);
+        assert_eq!(split_words("HTTPServer"), vec!["HTTP", "Server"]);
+        assert_eq!(split_words("Slot2"), vec!["Slot2"]);
+        assert_eq!(split_words("already_snake"), vec!["already", "snake"]);
+    }
+
+    #[test]
+    fn test_case() {
+        let name = "MotionBlockingNoLeaves";
+        assert_eq!(Case::Lower.apply(name), "motionblockingnoleaves");
+        assert_eq!(Case::Upper.apply(name), "MOTIONBLOCKINGNOLEAVES");
+        assert_eq!(Case::Snake.apply(name), "motion_blocking_no_leaves");
+        assert_eq!(
+            Case::ScreamingSnake.apply(name),
+            "MOTION_BLOCKING_NO_LEAVES"
+        );
+        assert_eq!(Case::Kebab.apply(name), "motion-blocking-no-leaves");
+        assert_eq!(Case::Camel.apply(name), "motionBlockingNoLeaves");
+        assert_eq!(Case::Pascal.apply(name), "MotionBlockingNoLeaves");
+    }
+}
@@ -1,3 +1,4 @@
+mod derive;
 mod parse_tree;
 
 extern crate proc_macro;
@@ -10,26 +11,27 @@ use itertools::Itertools;
 use parse_tree::ParseNode;
 use proc_macro::TokenStream;
 
-use proc_macro2::Ident;
+use proc_macro2::{Ident, Span, TokenTree};
 use quote::{quote, ToTokens};
 use syn::{
-    parse::Parse, parse_macro_input, parse_quote, punctuated::Punctuated, spanned::Spanned,
-    Attribute, AttributeArgs, Expr, Fields, Index, Item, ItemEnum, ItemMod, ItemStruct, LitStr,
-    Member, Token, Type,
+    ext::IdentExt, parenthesized, parse::Parse, parse_macro_input, parse_quote,
+    punctuated::Punctuated, spanned::Spanned, token, Attribute, DeriveInput, Expr, ExprLit, Field,
+    Fields, GenericArgument, GenericParam, Generics, Index, Item, ItemEnum, ItemMod, Lit, LitStr,
+    Member, Path, PathArguments, Token, Type,
 };
 
-use crate::parse_tree::ParseTree;
+use crate::{derive::Case, parse_tree::ParseTree};
 
-type StructFields = HashMap<Member, Type>;
+type StructFields = HashMap<Member, FieldInfo>;
 
 #[proc_macro_attribute]
 pub fn parser(args: TokenStream, input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as ItemMod);
-    let args = parse_macro_input!(args as AttributeArgs);
+    let args = parse_macro_input!(args as MacroArgs);
 
     let result = handle_parse_macro(args, input);
     let result = match result {
-        Ok(data) => data.into(),
+        Ok(data) => data,
         Err(error) => {
             let e = error.to_compile_error();
             quote! { #e }
@@ -39,15 +41,103 @@ pub fn parser(args: TokenStream, input: TokenStream) -> TokenStream {
     result.into()
 }
 
+/// Derives `CommandParse` and `Display` for an enum of keywords or a struct with `#[command("...")]` templates
+#[proc_macro_derive(CommandParse, attributes(command, arg))]
+pub fn derive_command_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let result = derive::handle_derive(input);
+    let result = match result {
+        Ok(data) => data,
+        Err(error) => error.to_compile_error(),
+    };
+
+    result.into()
+}
+
 fn handle_parse_macro(
-    args: AttributeArgs,
+    args: MacroArgs,
     mut input: ItemMod,
 ) -> syn::Result<proc_macro2::TokenStream> {
-    if args.len() > 0 {
-        return Err(syn::Error::new(args[0].span(), "Expected no arguments"));
+    let config = MacroConfig::new(args, &mut input)?;
+    expand_module(&mut input, &config)?;
+    Ok(input.to_token_stream())
+}
+
+/// Generates the parser for the target enum of `input` and all nested modules marked with `#[prefix(...)]`
+fn expand_module(input: &mut ItemMod, config: &MacroConfig) -> syn::Result<()> {
+    let mut subsets = Vec::new();
+    if let Some((_brace, content)) = input.content.as_mut() {
+        for item in content {
+            if let Item::Mod(module) = item {
+                if let Some(prefix) = extract_prefix(&mut module.attrs)? {
+                    let nested_config = MacroConfig {
+                        output_name: find_target_enum(module, None)?.ident.clone(),
+                        fallback: None,
+                        includes: Vec::new(),
+                        codegen: config.codegen.clone(),
+                    };
+                    expand_module(module, &nested_config)?;
+                    let module_name = &module.ident;
+                    let nested_enum = &nested_config.output_name;
+                    subsets.push(CommandSubset {
+                        variant: Ident::new(
+                            &Case::Pascal.apply(&module_name.to_string()),
+                            module_name.span(),
+                        ),
+                        path: parse_quote!(#module_name::#nested_enum),
+                        prefix,
+                    });
+                }
+            }
+        }
+    }
+    for include in &config.includes {
+        let segments: Vec<_> = include.segments.iter().collect();
+        let name = &segments[segments.len().saturating_sub(2)].ident;
+        subsets.push(CommandSubset {
+            variant: Ident::new(&Case::Pascal.apply(&name.to_string()), name.span()),
+            path: include.clone(),
+            prefix: String::new(),
+        });
     }
 
-    let config = MacroConfig::new(&mut input)?;
+    // Errors in templates are collected, so that all of them are reported at once
+    let mut errors = Errors::default();
+
+    // Every command subset becomes a variant which parses its prefix followed by a command of the subset.
+    // Subsets without a prefix are only tried after the literals of this parser, in the order they were given.
+    let target_enum = find_target_enum(input, Some(&config.output_name))?;
+    for subset in &subsets {
+        let CommandSubset {
+            variant,
+            path,
+            prefix,
+        } = subset;
+        if target_enum
+            .variants
+            .iter()
+            .any(|existing| existing.ident == *variant)
+        {
+            errors.push(syn::Error::new(
+                variant.span(),
+                format!(
+                    "`{}` would become the variant `{}`, which already exists. Nested modules and included enums are named after their module, so these have to be distinct",
+                    path.to_token_stream().to_string().replace(' ', ""),
+                    variant
+                ),
+            ));
+            continue;
+        }
+        let template = LitStr::new(&format!("{} $0", prefix), variant.span());
+        target_enum.variants.push(parse_quote! {
+            #[parse(#template)]
+            #variant(#path)
+        });
+    }
+
+    let mut usage_lines = Vec::new();
+    let mut root_literals = Vec::new();
     let mut parse_tree = ParseTree::new();
 
     let mut structs = Vec::new();
@@ -56,19 +146,44 @@ fn handle_parse_macro(
     if let Some((_brace, content)) = input.content.as_mut() {
         for item in content {
             if let Item::Struct(strukt) = item {
-                let fields = extract_struct_fields(&strukt)?;
+                let fields = match errors.collect(extract_struct_fields(&strukt.fields)) {
+                    Some(fields) => fields,
+                    None => continue,
+                };
+                strip_arg_attributes(&mut strukt.fields);
                 let mut has_attr = false;
 
                 let mut optional_args = HashSet::new();
 
                 let interesting_attributes =
-                    find_interesting_attributes(&mut strukt.attrs, &fields)?;
+                    match errors.collect(find_interesting_attributes(&mut strukt.attrs, &fields)) {
+                        Some(attributes) => attributes,
+                        None => continue,
+                    };
                 for matching_attribute in &interesting_attributes {
                     has_attr = true;
+                    errors.collect(
+                        config
+                            .codegen
+                            .check_template(&matching_attribute.parse_template),
+                    );
+                    usage_lines.push(
+                        config
+                            .codegen
+                            .usage_line(&matching_attribute.parse_template),
+                    );
+                    root_literals.extend(
+                        config
+                            .codegen
+                            .root_literal(&matching_attribute.parse_template),
+                    );
                     parse_tree.insert(
-                        matching_attribute.parse_template.iter().cloned(),
-                        strukt.ident.clone(),
-                        matching_attribute.kwargs.clone(),
+                        matching_attribute
+                            .parse_template
+                            .iter()
+                            .map(|node| config.codegen.tree_node(node)),
+                        strukt.ident.clone().into(),
+                        matching_attribute.values(),
                         fields.keys().cloned().collect(),
                     );
 
@@ -77,75 +192,280 @@ fn handle_parse_macro(
 
                 if has_attr {
                     let optional_args = optional_args.into_iter().collect();
-                    let display_impl =
-                        generate_display_impl(optional_args, &interesting_attributes, &strukt);
+                    let display_impl = generate_display_impl(
+                        optional_args,
+                        &interesting_attributes,
+                        &strukt.ident,
+                        &strukt.generics,
+                        &config.codegen,
+                    );
                     display_impls.push(display_impl);
-                    structs.push(strukt.ident.clone());
+                    structs.push((strukt.ident.clone(), strukt.generics.clone()));
                 }
             }
         }
     }
 
-    let target_enum = find_target_enum(&mut input)?;
-    if !target_enum.variants.is_empty() {
-        return Err(syn::Error::new(
-            target_enum.span(),
-            "The target enum must be empty",
-        ));
+    let krate = &config.codegen.krate;
+    let target_enum = find_target_enum(input, Some(&config.output_name))?;
+    let mut variant_display_arms = Vec::new();
+    let mut custom_variants = Vec::new();
+    for variant in target_enum.variants.iter_mut() {
+        let fields = match errors.collect(extract_struct_fields(&variant.fields)) {
+            Some(fields) => fields,
+            None => continue,
+        };
+        strip_arg_attributes(&mut variant.fields);
+        let interesting_attributes =
+            match errors.collect(find_interesting_attributes(&mut variant.attrs, &fields)) {
+                Some(attributes) => attributes,
+                None => continue,
+            };
+        let variant_name = &variant.ident;
+
+        let custom = match errors.collect(CustomVariant::extract(&mut variant.attrs)) {
+            Some(custom) => custom,
+            None => continue,
+        };
+        if let Some(custom) = custom {
+            if !interesting_attributes.is_empty() {
+                errors.push(syn::Error::new(
+                    variant.span(),
+                    "A variant cannot have both `#[custom]` and `#[parse(...)]` attributes",
+                ));
+                continue;
+            }
+            let ty = match &variant.fields {
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => &fields.unnamed[0].ty,
+                _ => {
+                    errors.push(syn::Error::new(
+                        variant.span(),
+                        "Custom variants must have exactly one unnamed field",
+                    ));
+                    continue;
+                }
+            };
+
+            let parse = match &custom.parse_with {
+                Some(parse_with) => quote! {#parse_with(input)},
+                None => quote! {<#ty as #krate::CommandParse>::parse_from_command(input)},
+            };
+            custom_variants.push(quote! {
+                #parse.map(|(rest, value)| (rest, Self::#variant_name(value)))
+            });
+            variant_display_arms.push(match &custom.display_with {
+                Some(display_with) => quote! {Self::#variant_name(val) => #display_with(val, f)},
+                None => quote! {Self::#variant_name(val) => write!(f, "{}", val)},
+            });
+            continue;
+        }
+
+        if interesting_attributes.is_empty() {
+            errors.push(syn::Error::new(
+                variant.span(),
+                "Expected a `#[parse(...)]` or `#[custom]` attribute on this variant",
+            ));
+            continue;
+        }
+
+        let is_subset = subsets.iter().any(|subset| subset.variant == *variant_name);
+        let mut optional_args = HashSet::new();
+        for matching_attribute in &interesting_attributes {
+            errors.collect(
+                config
+                    .codegen
+                    .check_template(&matching_attribute.parse_template),
+            );
+            if !is_subset {
+                usage_lines.push(
+                    config
+                        .codegen
+                        .usage_line(&matching_attribute.parse_template),
+                );
+            }
+            root_literals.extend(
+                config
+                    .codegen
+                    .root_literal(&matching_attribute.parse_template),
+            );
+            parse_tree.insert(
+                matching_attribute
+                    .parse_template
+                    .iter()
+                    .map(|node| config.codegen.tree_node(node)),
+                parse_quote!(Self::#variant_name),
+                matching_attribute.values(),
+                fields.keys().cloned().collect(),
+            );
+            optional_args.extend(matching_attribute.kwargs.keys().cloned());
+        }
+
+        let optional_args: Vec<_> = optional_args.into_iter().collect();
+        let display_match = generate_display_match(
+            &optional_args,
+            &interesting_attributes,
+            &config.codegen,
+            |member| escape_member(member).into_token_stream(),
+        );
+        let members = fields.keys();
+        let escaped_members = fields.keys().map(escape_member);
+        variant_display_arms.push(quote! {
+            Self::#variant_name { #(#members: #escaped_members),* } => #display_match
+        });
+    }
+    // Generic structs are stored with their parameters, which the target enum has to declare as well
+    let enum_params: Vec<String> = target_enum
+        .generics
+        .params
+        .iter()
+        .map(generic_param_name)
+        .collect();
+    for (_, generics) in &structs {
+        for param in &generics.params {
+            let name = generic_param_name(param);
+            if !enum_params.contains(&name) {
+                errors.push(syn::Error::new(
+                    param.span(),
+                    format!(
+                        "The target enum has to declare the generic parameter `{}` as well",
+                        name
+                    ),
+                ));
+            }
+        }
     }
-    for strukt in &structs {
+    errors.finish()?;
+    let enum_generics = with_command_parse_bounds(&target_enum.generics, krate);
+    for (strukt, generics) in &structs {
+        let (_, ty_generics, _) = generics.split_for_impl();
         target_enum.variants.push(parse_quote! {
-            #strukt(#strukt)
+            #strukt(#strukt #ty_generics)
         })
     }
+    let mut fallback_parse = None;
+    if let Some(fallback) = &config.fallback {
+        target_enum.variants.push(parse_quote! {
+            /// The raw text of a command which matches no template
+            #fallback(::std::string::String)
+        });
+        // Blank input is no command, so it is not kept by the fallback
+        fallback_parse = Some(quote! {
+            if input.trim().is_empty() {
+                ::std::result::Result::Err(input)
+            } else {
+                ::std::result::Result::Ok(("", Self::#fallback(::std::string::ToString::to_string(input))))
+            }
+        });
+        variant_display_arms.push(quote! {Self::#fallback(raw) => f.write_str(raw)});
+    }
 
     if let Some((_brace, content)) = input.content.as_mut() {
         let enum_name = &config.output_name;
+        let (impl_generics, ty_generics, where_clause) = enum_generics.split_for_impl();
+        let struct_names = structs.iter().map(|(strukt, _)| strukt);
         content.push(parse_quote! {
-            impl ::std::fmt::Display for #enum_name {
+            impl #impl_generics ::std::fmt::Display for #enum_name #ty_generics #where_clause {
                 fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
-                    match self {#(
-                        Self::#structs(val) => write!(f, "{}", val)
-                    ),*}
+                    match self {
+                        #(Self::#struct_names(val) => write!(f, "{}", val),)*
+                        #(#variant_display_arms,)*
+                    }
                 }
             }
         });
 
-        for strukt in &structs {
+        for (strukt, generics) in &structs {
+            let (_, struct_ty_generics, _) = generics.split_for_impl();
             content.push(parse_quote! {
-                impl ::std::convert::From<#strukt> for #enum_name {
-                    fn from(value: #strukt) -> Self {
+                impl #impl_generics ::std::convert::From<#strukt #struct_ty_generics> for #enum_name #ty_generics #where_clause {
+                    fn from(value: #strukt #struct_ty_generics) -> Self {
                         Self::#strukt(value)
                     }
                 }
             });
         }
 
-        content.extend(display_impls.into_iter());
+        content.extend(display_impls);
 
-        let command_parse_impl = generate_command_parse_impl(&parse_tree, &config.output_name);
+        let command_parse_impl = generate_command_parse_impl(
+            &parse_tree,
+            &config.output_name,
+            &enum_generics,
+            &custom_variants,
+            fallback_parse.as_ref(),
+            &config.codegen,
+        );
         content.push(command_parse_impl);
+        content.extend(generate_command_set_impl(
+            enum_name,
+            &enum_generics,
+            usage_lines,
+            root_literals,
+            &subsets,
+            &config.codegen,
+        ));
 
+        // Commands which only match a template partially are kept by the fallback as well
+        let from_str_body = match &config.fallback {
+            Some(fallback) => quote! {
+                #krate::parse_command(s).or_else(|error| {
+                    if s.trim().is_empty() {
+                        ::std::result::Result::Err(::std::string::String::from(error))
+                    } else {
+                        ::std::result::Result::Ok(Self::#fallback(::std::string::ToString::to_string(s)))
+                    }
+                })
+            },
+            None => quote! {
+                #krate::parse_command(s).map_err(::std::string::String::from)
+            },
+        };
         let from_string_impl = parse_quote! {
-            impl ::std::str::FromStr for #enum_name {
+            impl #impl_generics ::std::str::FromStr for #enum_name #ty_generics #where_clause {
                 type Err = ::std::string::String;
 
                 fn from_str(s: &::std::primitive::str) -> ::std::result::Result<Self, ::std::string::String> {
-                    ::command_parser::parse_command(s).map_err(::std::string::String::from)
+                    #from_str_body
                 }
             }
         };
         content.push(from_string_impl);
     }
 
-    Ok(input.to_token_stream())
+    Ok(())
 }
 
 fn generate_display_impl(
     optional_args: Vec<Member>,
     interesting_attributes: &[ParseAttr],
-    strukt: &ItemStruct,
+    struct_name: &Ident,
+    generics: &Generics,
+    codegen: &CodegenConfig,
 ) -> Item {
+    let display_match =
+        generate_display_match(&optional_args, interesting_attributes, codegen, |member| {
+            quote! {&self.#member}
+        });
+    let generics = with_command_parse_bounds(generics, &codegen.krate);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let val: Item = parse_quote! {
+        impl #impl_generics ::std::fmt::Display for #struct_name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                #display_match
+            }
+        }
+    };
+    val
+}
+
+/// Generates a match which writes the template that corresponds to the values of the optional arguments.
+/// `access` generates an expression which references the given member.
+fn generate_display_match(
+    optional_args: &[Member],
+    interesting_attributes: &[ParseAttr],
+    codegen: &CodegenConfig,
+    access: impl Fn(&Member) -> proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
     let mut pattern_matches = Vec::new();
     let mut write_actions = Vec::new();
     for matching_attribute in interesting_attributes {
@@ -160,56 +480,122 @@ fn generate_display_impl(
         };
         pattern_matches.push(pattern_match);
 
+        // Escape the separator in case it is a brace
+        let separator = match codegen.separator {
+            '{' => "{{".to_string(),
+            '}' => "}}".to_string(),
+            separator => separator.to_string(),
+        };
+        let krate = &codegen.krate;
+        let separator_char = codegen.separator;
+
         let mut arg_assignments = Vec::new();
-        let mut template_parts = Vec::new();
-        for template_part in &matching_attribute.parse_template {
+        let mut template_parts = String::new();
+        let template = &matching_attribute.parse_template;
+        for (index, template_part) in template.iter().enumerate() {
+            let is_last = index + 1 == template.len();
+            let next_is_repeat = matches!(template.get(index + 1), Some(ParseNode::Repeat { .. }));
             match template_part {
                 ParseNode::Pass | ParseNode::EndOfInput { .. } => unreachable!(),
-                ParseNode::Function { name: _, binding } => {
-                    arg_assignments.push(quote! {&self.#binding});
-                    template_parts.push("{}")
+                ParseNode::Function {
+                    binding,
+                    display_with,
+                    ..
+                } => {
+                    let value = access(binding);
+                    arg_assignments.push(match display_with {
+                        Some(display_with) => quote! {#krate::display_with(#value, #display_with)},
+                        None => value,
+                    });
+                    template_parts.push_str("{}");
+                }
+                ParseNode::Repeat { name: _, binding } => {
+                    // Repeated values write the separators around them, so that no values leave no gap.
+                    // They are preceded by the separator unless they start the template.
+                    let values = access(binding);
+                    let leading = index > 0;
+                    let trailing = index == 0 && !is_last;
+                    arg_assignments.push(quote! {
+                        #krate::display_repeated(#values, #separator_char, #leading, #trailing)
+                    });
+                    template_parts.push_str("{}");
+                    if trailing {
+                        continue;
+                    }
                 }
                 ParseNode::Literal(val) => {
-                    template_parts.push(&val);
+                    template_parts.push_str(val);
                 }
             };
+            if !is_last && !next_is_repeat {
+                template_parts.push_str(&separator);
+            }
         }
 
-        let template_parts = template_parts.into_iter().join(" ");
         let write_action = quote! {
             write!(f, #template_parts, #(#arg_assignments),*)
         };
         write_actions.push(write_action);
     }
-    let optional_args = quote! {
-        (#(
-            &self.#optional_args
-        ),*)
-    };
-    let struct_name = &strukt.ident;
-    let val: Item = parse_quote! {
-        impl ::std::fmt::Display for #struct_name {
-            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
-                match #optional_args {
-                    #(
-                        #pattern_matches => #write_actions
-                    ),*,
-                    _ => unreachable!("Cannot convert invalid struct to string: Does not respect parsing invariants")
-                }
-            }
+    let optional_args = optional_args.iter().map(access);
+    quote! {
+        match (#(#optional_args),*) {
+            #(
+                #pattern_matches => #write_actions
+            ),*,
+            _ => unreachable!("Cannot convert invalid struct to string: Does not respect parsing invariants")
         }
-    };
-    val
+    }
 }
 
-fn generate_command_parse_impl(parse_tree: &ParseTree, enum_name: &Ident) -> Item {
+/// Generates the `CommandParse` impl which matches the input against the parse tree.
+/// Each of the `custom_variants` is tried in order as well, if the tree fails or leaves input.
+/// Of these, the match which leaves the least input is used, preferring the tree and earlier variants.
+/// The `fallback` is only tried if all of them fail.
+/// Custom variants and the fallback are expressions which parse `input` and evaluate to the
+/// same result type as `parse_from_command`.
+fn generate_command_parse_impl(
+    parse_tree: &ParseTree,
+    enum_name: &Ident,
+    generics: &Generics,
+    custom_variants: &[proc_macro2::TokenStream],
+    fallback: Option<&proc_macro2::TokenStream>,
+    codegen: &CodegenConfig,
+) -> Item {
+    let krate = &codegen.krate;
     match &parse_tree.payload {
         ParseNode::Pass => {
-            let ts = _generate_from_string_impl_inner(&parse_tree.options);
+            let ts = _generate_from_string_impl_inner(&parse_tree.options, enum_name, codegen);
+            let body = if custom_variants.is_empty() && fallback.is_none() {
+                ts
+            } else {
+                let fallback = fallback.into_iter();
+                quote! {
+                    let input = rest;
+                    let result: ::std::result::Result<(&::std::primitive::str, Self), &::std::primitive::str> = { #ts };
+                    #(
+                        let result = match result {
+                            ::std::result::Result::Err(error) => (#custom_variants).map_err(|_: &::std::primitive::str| error),
+                            ::std::result::Result::Ok((rest, value)) if !rest.is_empty() => match #custom_variants {
+                                ::std::result::Result::Ok((custom_rest, custom)) if custom_rest.len() < rest.len() => {
+                                    ::std::result::Result::Ok((custom_rest, custom))
+                                }
+                                _ => ::std::result::Result::Ok((rest, value)),
+                            },
+                            result => result,
+                        };
+                    )*
+                    #(
+                        let result = result.or_else(|error| (#fallback).map_err(|_: &::std::primitive::str| error));
+                    )*
+                    result
+                }
+            };
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
             parse_quote! {
-                impl ::command_parser::CommandParse for #enum_name {
+                impl #impl_generics #krate::CommandParse for #enum_name #ty_generics #where_clause {
                     fn parse_from_command(rest: &::std::primitive::str) -> ::std::result::Result<(&::std::primitive::str, Self), &::std::primitive::str> {
-                        #ts
+                        #body
                     }
                 }
             }
@@ -218,26 +604,81 @@ fn generate_command_parse_impl(parse_tree: &ParseTree, enum_name: &Ident) -> Ite
     }
 }
 
-fn _generate_from_string_impl_inner(options: &[ParseTree]) -> proc_macro2::TokenStream {
+fn _generate_from_string_impl_inner(
+    options: &[ParseTree],
+    enum_name: &Ident,
+    codegen: &CodegenConfig,
+) -> proc_macro2::TokenStream {
     let mut literal_matches: Vec<&str> = Vec::new();
     let mut literal_matches_and_then = Vec::new();
 
     let mut stop_matching = None;
 
-    let mut function_matches_name = Vec::new();
+    let krate = &codegen.krate;
+    let mut function_matches_parse = Vec::new();
     let mut function_matches_binding = Vec::new();
     let mut function_matches_and_then = Vec::new();
 
     for option in options {
         match &option.payload {
             ParseNode::Literal(lit) => {
-                literal_matches.push(&lit);
-                literal_matches_and_then.push(_generate_from_string_impl_inner(&option.options));
+                literal_matches.push(lit);
+                literal_matches_and_then.push(_generate_from_string_impl_inner(
+                    &option.options,
+                    enum_name,
+                    codegen,
+                ));
+            }
+            ParseNode::Function {
+                name,
+                binding,
+                parse_with,
+                ..
+            } => {
+                let parse = match parse_with {
+                    Some(parse_with) => quote! {#parse_with(rest)},
+                    None => quote! {<#name as #krate::CommandParse>::parse_from_command(rest)},
+                };
+                let parse = codegen.guard_redirect(name, enum_name, parse);
+                let is_last = option
+                    .options
+                    .iter()
+                    .all(|option| matches!(option.payload, ParseNode::EndOfInput { .. }));
+                function_matches_parse.push(if is_last {
+                    parse
+                } else {
+                    codegen.parse_word(parse)
+                });
+                function_matches_binding.push(binding);
+                function_matches_and_then.push(_generate_from_string_impl_inner(
+                    &option.options,
+                    enum_name,
+                    codegen,
+                ));
             }
-            ParseNode::Function { name, binding } => {
-                function_matches_name.push(name);
+            ParseNode::Repeat { name, binding } => {
+                // The element type is inferred from the type of the field
+                let parse = quote! {
+                    ::std::result::Result::<_, &::std::primitive::str>::Ok(
+                        #krate::parse_multiple_commands(rest)
+                    )
+                };
+                let parse = codegen.guard_redirect(name, enum_name, parse);
+                let is_last = option
+                    .options
+                    .iter()
+                    .all(|option| matches!(option.payload, ParseNode::EndOfInput { .. }));
+                function_matches_parse.push(if is_last {
+                    parse
+                } else {
+                    codegen.parse_word(parse)
+                });
                 function_matches_binding.push(binding);
-                function_matches_and_then.push(_generate_from_string_impl_inner(&option.options));
+                function_matches_and_then.push(_generate_from_string_impl_inner(
+                    &option.options,
+                    enum_name,
+                    codegen,
+                ));
             }
             ParseNode::EndOfInput {
                 defaults,
@@ -248,13 +689,6 @@ fn _generate_from_string_impl_inner(options: &[ParseTree]) -> proc_macro2::Token
         }
     }
 
-    fn escape_member(member: &Member) -> Ident {
-        match member {
-            Member::Named(ident) => Ident::new(&format!("_{}", ident), ident.span()),
-            Member::Unnamed(idx) => Ident::new(&format!("_{}", idx.index), idx.span()),
-        }
-    }
-
     let match_on_stop = if let Some((defaults, struct_name, idents)) = stop_matching {
         let escaped_idents = idents.iter().map(escape_member);
         let escaped_default_idents = defaults.keys().map(escape_member);
@@ -263,13 +697,13 @@ fn _generate_from_string_impl_inner(options: &[ParseTree]) -> proc_macro2::Token
             #(
                 let #escaped_default_idents = #default_values;
             )*
-            return ::std::result::Result::Ok((rest, #struct_name {#(
+            ::std::result::Result::Ok((rest, #struct_name {#(
                 #idents: #escaped_idents
             ),*}.into()))
         }}
     } else {
         quote! {
-            return ::std::result::Result::Err(rest)
+            ::std::result::Result::Err(rest)
         }
     };
     let match_on_function = if !function_matches_binding.is_empty() {
@@ -278,7 +712,7 @@ fn _generate_from_string_impl_inner(options: &[ParseTree]) -> proc_macro2::Token
             .map(|member| escape_member(member));
         quote! {
             #(
-                if let Ok((rest, #function_matches_binding_escaped)) = <#function_matches_name as ::command_parser::CommandParse>::parse_from_command(rest) {
+                if let Ok((rest, #function_matches_binding_escaped)) = #function_matches_parse {
                     #function_matches_and_then
                 }
             )else*
@@ -292,9 +726,15 @@ fn _generate_from_string_impl_inner(options: &[ParseTree]) -> proc_macro2::Token
         }
     };
     let match_on_literal = if !literal_matches.is_empty() {
+        let separator = codegen.separator;
+        let next = if codegen.case_insensitive {
+            quote! {next.to_lowercase().as_str()}
+        } else {
+            quote! {next}
+        };
         quote! {
-            let (next, rest_next) = rest.split_once(" ").unwrap_or((rest, ""));
-            match next {
+            let (next, rest_next) = rest.split_once(#separator).unwrap_or((rest, ""));
+            match #next {
                 #(
                     #literal_matches => {
                         let rest = rest_next;
@@ -313,50 +753,211 @@ fn _generate_from_string_impl_inner(options: &[ParseTree]) -> proc_macro2::Token
     match_on_literal
 }
 
-fn extract_struct_fields(strukt: &ItemStruct) -> syn::Result<StructFields> {
-    let mut res = HashMap::new();
-    match &strukt.fields {
-        Fields::Named(named) => {
-            for field in &named.named {
-                res.insert(
-                    Member::Named(field.ident.as_ref().unwrap().clone()),
-                    field.ty.clone(),
-                );
+/// Generates the `CommandSet` impl of the target enum and checks that the root literals of
+/// its own commands and of subsets without a prefix do not overlap
+fn generate_command_set_impl(
+    enum_name: &Ident,
+    generics: &Generics,
+    usage_lines: Vec<String>,
+    root_literals: Vec<String>,
+    subsets: &[CommandSubset],
+    codegen: &CodegenConfig,
+) -> Vec<Item> {
+    let krate = &codegen.krate;
+    let separator = codegen.separator;
+    let usage_lines = usage_lines.into_iter().unique();
+    let root_literals: Vec<_> = root_literals.into_iter().unique().collect();
+    let root_literal_count = root_literals.len();
+
+    // Subsets with a prefix are dispatched by the literals of the prefix
+    let unprefixed: Vec<_> = subsets
+        .iter()
+        .filter(|subset| subset.prefix.trim().is_empty())
+        .map(|subset| &subset.path)
+        .collect();
+    let prefixes = subsets.iter().map(|subset| {
+        let words = subset.prefix.split_ascii_whitespace();
+        words
+            .map(|word| format!("{}{}", word, separator))
+            .collect::<String>()
+    });
+    let subset_paths = subsets.iter().map(|subset| &subset.path);
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut items = vec![parse_quote! {
+        impl #impl_generics #krate::CommandSet for #enum_name #ty_generics #where_clause {
+            const SEPARATOR: ::std::primitive::char = #separator;
+            const ROOT_LITERALS: &'static [&'static ::std::primitive::str] =
+                &#krate::concat_literals::<{ #root_literal_count #(+ <#unprefixed as #krate::CommandSet>::ROOT_LITERALS.len())* }>(&[
+                    &[#(#root_literals),*],
+                    #(<#unprefixed as #krate::CommandSet>::ROOT_LITERALS),*
+                ]);
+
+            fn usage() -> ::std::vec::Vec<::std::string::String> {
+                let mut usage: ::std::vec::Vec<::std::string::String> =
+                    ::std::vec![#(::std::string::String::from(#usage_lines)),*];
+                #(
+                    usage.extend(
+                        <#subset_paths as #krate::CommandSet>::usage()
+                            .into_iter()
+                            .map(|line| ::std::format!("{}{}", #prefixes, line)),
+                    );
+                )*
+                usage
             }
         }
-        Fields::Unnamed(unnamed) => {
-            for (index, field) in unnamed.unnamed.iter().enumerate() {
-                res.insert(
-                    Member::Unnamed(Index {
-                        index: index.try_into().unwrap(),
-                        span: field.span(),
-                    }),
-                    field.ty.clone(),
+    }];
+
+    let own_name = enum_name.to_string();
+    let own_literals = quote! {&[#(#root_literals),*]};
+    for (index, path) in unprefixed.iter().enumerate() {
+        let path_name = path.to_token_stream().to_string().replace(' ', "");
+        let path_literals = quote! {<#path as #krate::CommandSet>::ROOT_LITERALS};
+        let message = format!(
+            "Commands of `{}` start with the same literal as commands of `{}`",
+            path_name, own_name
+        );
+        items.push(parse_quote! {
+            const _: () = ::std::assert!(
+                !#krate::literals_overlap(#own_literals, #path_literals),
+                #message
+            );
+        });
+        for other in &unprefixed[..index] {
+            let other_name = other.to_token_stream().to_string().replace(' ', "");
+            let message = format!(
+                "Commands of `{}` start with the same literal as commands of `{}`",
+                path_name, other_name
+            );
+            items.push(parse_quote! {
+                const _: () = ::std::assert!(
+                    !#krate::literals_overlap(
+                        <#other as #krate::CommandSet>::ROOT_LITERALS,
+                        #path_literals
+                    ),
+                    #message
                 );
-            }
+            });
         }
-        Fields::Unit => {}
     }
+    items
+}
+
+/// The name of the local variable which holds the value of `member`
+fn escape_member(member: &Member) -> Ident {
+    match member {
+        Member::Named(ident) => Ident::new(&format!("_{}", ident), ident.span()),
+        Member::Unnamed(idx) => Ident::new(&format!("_{}", idx.index), idx.span()),
+    }
+}
 
+fn extract_struct_fields(fields: &Fields) -> syn::Result<StructFields> {
+    let mut res = HashMap::new();
+    for (index, field) in fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index {
+                index: index.try_into().unwrap(),
+                span: field.span(),
+            }),
+        };
+        res.insert(member, FieldInfo::new(field)?);
+    }
     Ok(res)
 }
 
+/// Removes the `#[arg(...)]` attributes, which were read by `extract_struct_fields`
+fn strip_arg_attributes(fields: &mut Fields) {
+    for field in fields.iter_mut() {
+        field.attrs.retain(|attr| !attr.path.is_ident("arg"));
+    }
+}
+
+/// A field of a struct or variant together with the options of its `#[arg(...)]` attribute
+#[derive(Debug, Clone)]
+struct FieldInfo {
+    ty: Type,
+    /// The value of the field if a template does not mention it
+    default: Option<Expr>,
+    /// A function like `CommandParse::parse_from_command` which parses the field
+    parse_with: Option<Path>,
+    /// A function like `Display::fmt` which writes the field
+    display_with: Option<Path>,
+}
+
+impl FieldInfo {
+    fn new(field: &Field) -> syn::Result<Self> {
+        let mut info = FieldInfo {
+            ty: field.ty.clone(),
+            default: None,
+            parse_with: None,
+            display_with: None,
+        };
+        for attr in field.attrs.iter().filter(|attr| attr.path.is_ident("arg")) {
+            let args = attr.parse_args_with(Punctuated::<MacroArg, Token![,]>::parse_terminated)?;
+            for arg in args {
+                if arg.list.is_some() {
+                    let message = if arg.name == "default" {
+                        "Expected `default` or a value like `default = ...`".to_string()
+                    } else {
+                        format!("Expected a value like `{} = ...`", arg.name)
+                    };
+                    return Err(syn::Error::new(arg.name.span(), message));
+                }
+                let is_duplicate = match (arg.name.to_string().as_str(), arg.value) {
+                    ("default", value) => info
+                        .default
+                        .replace(value.unwrap_or_else(
+                            || parse_quote!(::std::default::Default::default()),
+                        ))
+                        .is_some(),
+                    ("parse_with", Some(value)) => {
+                        info.parse_with.replace(expect_path(&value)?).is_some()
+                    }
+                    ("display_with", Some(value)) => {
+                        info.display_with.replace(expect_path(&value)?).is_some()
+                    }
+                    (name @ ("parse_with" | "display_with"), None) => {
+                        return Err(syn::Error::new(
+                            arg.name.span(),
+                            format!("Expected a path like `{} = my_module::function`", name),
+                        ))
+                    }
+                    _ => {
+                        return Err(syn::Error::new(
+                            arg.name.span(),
+                            "Unknown argument, expected one of `default`, `parse_with` or `display_with`",
+                        ))
+                    }
+                };
+                if is_duplicate {
+                    return Err(syn::Error::new(arg.name.span(), "Duplicate argument"));
+                }
+            }
+        }
+        Ok(info)
+    }
+}
+
 fn find_interesting_attributes(
     attrs: &mut Vec<Attribute>,
     fields: &StructFields,
 ) -> syn::Result<Vec<ParseAttr>> {
+    let mut errors = Errors::default();
     let mut other_attributes = Vec::with_capacity(attrs.len());
     let mut found_attributes = Vec::new();
     for attr in attrs.drain(..) {
         if attr.path.is_ident("parse") {
-            let attr_data = attr.parse_args_with(AttributeData::parse)?;
-            let parse_attrs = ParseAttr::new(attr_data, &fields)?;
-            found_attributes.push(parse_attrs);
+            let parse_attr = attr
+                .parse_args_with(AttributeData::parse)
+                .and_then(|attr_data| ParseAttr::new(attr_data, fields));
+            found_attributes.extend(errors.collect(parse_attr));
         } else {
             other_attributes.push(attr);
         }
     }
     *attrs = other_attributes;
+    errors.finish()?;
     Ok(found_attributes)
 }
 
@@ -364,63 +965,310 @@ fn find_interesting_attributes(
 struct ParseAttr {
     parse_template: Vec<ParseNode>,
     kwargs: HashMap<Member, Expr>,
+    /// The `#[arg(default)]` values of fields which the template does not mention
+    field_defaults: HashMap<Member, Expr>,
 }
 
 impl ParseAttr {
+    /// The values of all fields which are not parsed by the template
+    fn values(&self) -> HashMap<Member, Expr> {
+        let mut values = self.field_defaults.clone();
+        values.extend(self.kwargs.clone());
+        values
+    }
+
     pub fn new(attr_data: AttributeData, fields: &StructFields) -> syn::Result<Self> {
+        let mut errors = Errors::default();
         let mut parse_template = Vec::new();
-        for part in attr_data.parse_template.value().split_ascii_whitespace() {
-            let parse_node = match part.strip_prefix('$') {
+        for part in split_template(&attr_data.parse_template) {
+            let span = part.span;
+            let parse_node = match part.text.strip_prefix('$') {
                 Some(var) => {
+                    let (var, repeat) = match var.strip_suffix('*') {
+                        Some(var) => (var, true),
+                        None => (var, false),
+                    };
                     let member = match var.parse::<u32>() {
-                        Ok(val) => Member::Unnamed(Index {
-                            span: attr_data.parse_template.span(),
-                            index: val,
-                        }),
-                        Err(_) => Member::Named(Ident::new(var, attr_data.parse_template.span())),
+                        Ok(val) => Member::Unnamed(Index { span, index: val }),
+                        Err(_) => match syn::parse_str::<Ident>(var) {
+                            Ok(_) => Member::Named(Ident::new(var, span)),
+                            Err(_) => {
+                                errors.push(
+                                    part.error(format!("'{}' is not a valid field name", var)),
+                                );
+                                continue;
+                            }
+                        },
                     };
-                    ParseNode::Function {
-                        name: fields
-                            .get(&member)
-                            .ok_or_else(|| {
-                                syn::Error::new(
-                                    attr_data.parse_template.span(),
-                                    format!("Could not find '{}' in this struct", var),
-                                )
-                            })?
-                            .clone(),
-                        binding: member,
+                    let field = match fields.get(&member) {
+                        Some(field) => field,
+                        None => {
+                            errors.push(
+                                part.error(format!("Could not find '{}' in this struct", var)),
+                            );
+                            continue;
+                        }
+                    };
+                    if is_bound(&parse_template, &member) {
+                        errors.push(part.error(format!(
+                            "'{}' is parsed more than once by this template",
+                            var
+                        )));
+                        continue;
+                    }
+                    if repeat {
+                        if field.parse_with.is_some() || field.display_with.is_some() {
+                            errors.push(part.error(
+                                format!(
+                                    "'{}' cannot be repeated because it has a custom parse or display function",
+                                    var
+                                ),
+                            ));
+                        }
+                        ParseNode::Repeat {
+                            name: field.ty.clone(),
+                            binding: member,
+                        }
+                    } else {
+                        ParseNode::Function {
+                            name: field.ty.clone(),
+                            binding: member,
+                            parse_with: field.parse_with.clone(),
+                            display_with: field.display_with.clone(),
+                        }
                     }
                 }
-                None => ParseNode::Literal(part.to_string()),
+                None => ParseNode::Literal(part.text),
             };
             parse_template.push(parse_node);
         }
 
         let mut defaults = HashMap::new();
         for kwarg in attr_data.defaults {
-            defaults.insert(kwarg.member, kwarg.value);
+            let name = member_name(&kwarg.member);
+            let span = kwarg.member.span();
+            let error = if !fields.contains_key(&kwarg.member) {
+                format!("Could not find '{}' in this struct", name)
+            } else if is_bound(&parse_template, &kwarg.member) {
+                format!("'{}' is already parsed by the template", name)
+            } else if defaults.insert(kwarg.member, kwarg.value).is_some() {
+                format!("'{}' is given a value more than once", name)
+            } else {
+                continue;
+            };
+            errors.push(syn::Error::new(span, error));
         }
 
+        // Fields which are neither parsed nor set by the template fall back to their `#[arg(default)]`
+        let mut field_defaults = HashMap::new();
+        let mut unbound = Vec::new();
+        for (member, field) in fields {
+            if is_bound(&parse_template, member) || defaults.contains_key(member) {
+                continue;
+            }
+            match &field.default {
+                Some(default) => {
+                    field_defaults.insert(member.clone(), default.clone());
+                }
+                None => unbound.push(member_name(member)),
+            }
+        }
+        if !unbound.is_empty() {
+            unbound.sort();
+            let names = unbound.iter().map(|name| format!("'{}'", name)).join(", ");
+            errors.push(syn::Error::new(
+                attr_data.parse_template.span(),
+                format!(
+                    "This template does not set {}. Fields can be parsed like `${}`, set like `{} = ...` or given `#[arg(default)]`",
+                    names, unbound[0], unbound[0]
+                ),
+            ));
+        }
+
+        errors.finish()?;
         Ok(ParseAttr {
             kwargs: defaults,
+            field_defaults,
             parse_template,
         })
     }
 }
 
-/// Finds the target enum, which is the first enum in the module
-fn find_target_enum(module: &mut ItemMod) -> syn::Result<&mut ItemEnum> {
-    let span = module.span();
-    module.content.as_mut().and_then(|(_, content)| {
-        content.iter_mut().find_map(|item| {
-            if let Item::Enum(val) = item {
-                Some(val)
-            } else {
+/// A whitespace separated part of a template, like `add` or `$target`
+struct TemplatePart {
+    text: String,
+    /// The span of the part, or of the whole template if that of the part cannot be computed
+    span: Span,
+    /// The column of the part inside the template, if `span` covers the whole template
+    column: Option<usize>,
+}
+
+impl TemplatePart {
+    /// Creates an error at this part, which names the part if the span does not point at it
+    fn error(&self, message: String) -> syn::Error {
+        match self.column {
+            Some(column) => syn::Error::new(
+                self.span,
+                format!(
+                    "{} (at `{}`, column {} of the template)",
+                    message, self.text, column
+                ),
+            ),
+            None => syn::Error::new(self.span, message),
+        }
+    }
+}
+
+/// Splits a template into its whitespace separated parts, each with its span inside the string literal.
+/// Stable compilers cannot create spans inside of literals, so the span of the whole literal is used there.
+fn split_template(template: &LitStr) -> Vec<TemplatePart> {
+    let value = template.value();
+    let token = match template.to_token_stream().into_iter().next() {
+        Some(TokenTree::Literal(token)) => Some(token),
+        _ => None,
+    };
+    // The offset of the value inside the literal, if the value is written without escapes
+    let offset = token.as_ref().and_then(|token| {
+        let source = token.to_string();
+        let start = source.find('"')? + 1;
+        source[start..].starts_with(value.as_str()).then_some(start)
+    });
+
+    let mut parts = Vec::new();
+    let mut rest = value.as_str();
+    while let Some(start) = rest.find(|c: char| !c.is_ascii_whitespace()) {
+        let end = rest[start..]
+            .find(|c: char| c.is_ascii_whitespace())
+            .map_or(rest.len(), |end| start + end);
+        let position = value.len() - rest.len();
+        let subspan = token.as_ref().zip(offset).and_then(|(token, offset)| {
+            token.subspan(offset + position + start..offset + position + end)
+        });
+        parts.push(TemplatePart {
+            text: rest[start..end].to_string(),
+            span: subspan.unwrap_or_else(|| template.span()),
+            column: match subspan {
+                Some(_) => None,
+                None => Some(value[..position + start].chars().count() + 1),
+            },
+        });
+        rest = &rest[end..];
+    }
+    parts
+}
+
+/// Collects several errors, so that they can be reported together
+#[derive(Default)]
+struct Errors(Option<syn::Error>);
+
+impl Errors {
+    fn push(&mut self, error: syn::Error) {
+        match &mut self.0 {
+            Some(errors) => errors.combine(error),
+            None => self.0 = Some(error),
+        }
+    }
+
+    /// Records the error of `result`, if any, and returns its value
+    fn collect<T>(&mut self, result: syn::Result<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.push(error);
                 None
             }
+        }
+    }
+
+    fn finish(self) -> syn::Result<()> {
+        match self.0 {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Adds a `CommandParse` bound to every type parameter, because fields of these types are parsed and displayed
+fn with_command_parse_bounds(generics: &Generics, krate: &Path) -> Generics {
+    let mut generics = generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(#krate::CommandParse));
+    }
+    generics
+}
+
+/// The name of a generic parameter, like `'a`, `T` or `N`
+fn generic_param_name(param: &GenericParam) -> String {
+    match param {
+        GenericParam::Type(param) => param.ident.to_string(),
+        GenericParam::Lifetime(param) => param.lifetime.to_string(),
+        GenericParam::Const(param) => param.ident.to_string(),
+    }
+}
+
+/// Whether `member` is parsed by an argument of the template
+fn is_bound(template: &[ParseNode], member: &Member) -> bool {
+    template.iter().any(|node| match node {
+        ParseNode::Function { binding, .. } | ParseNode::Repeat { binding, .. } => {
+            binding == member
+        }
+        _ => false,
+    })
+}
+
+/// The name of a member as it is written in templates, like `player` or `0`
+fn member_name(member: &Member) -> String {
+    match member {
+        Member::Named(ident) => ident.to_string(),
+        Member::Unnamed(index) => index.index.to_string(),
+    }
+}
+
+/// A command set which becomes a variant of the target enum: a nested module or an included enum
+struct CommandSubset {
+    variant: Ident,
+    path: Path,
+    /// The literals which are parsed before a command of the subset, separated by whitespace
+    prefix: String,
+}
+
+/// Removes the `#[prefix("...")]` attribute of a nested module and returns its literal prefix.
+/// A bare `#[prefix]` nests the module without a prefix.
+fn extract_prefix(attrs: &mut Vec<Attribute>) -> syn::Result<Option<String>> {
+    let index = match attrs.iter().position(|attr| attr.path.is_ident("prefix")) {
+        Some(index) => index,
+        None => return Ok(None),
+    };
+    let attr = attrs.remove(index);
+    if attr.tokens.is_empty() {
+        return Ok(Some(String::new()));
+    }
+    let prefix: LitStr = attr.parse_args()?;
+    Ok(Some(prefix.value()))
+}
+
+/// Finds the target enum, which is the enum called `name` or the first enum in the module if no name is given
+fn find_target_enum<'a>(
+    module: &'a mut ItemMod,
+    name: Option<&Ident>,
+) -> syn::Result<&'a mut ItemEnum> {
+    let span = module.span();
+    let target_enum = module.content.as_mut().and_then(|(_, content)| {
+        content.iter_mut().find_map(|item| match item {
+            Item::Enum(val) if name.map_or(true, |name| val.ident == *name) => Some(val),
+            _ => None,
         })
-    }).ok_or_else(|| syn::Error::new(span, "Could not find target enum. The target enum has to be the first enum in the module"))
+    });
+    target_enum.ok_or_else(|| match name {
+        Some(name) => syn::Error::new(
+            name.span(),
+            format!("Could not find the enum `{}` in this module", name),
+        ),
+        None => syn::Error::new(
+            span,
+            "Could not find target enum. The target enum has to be the first enum in the module",
+        ),
+    })
 }
 
 struct AttributeData {
@@ -458,16 +1306,438 @@ impl Parse for KeywordArg {
     }
 }
 
+/// A hand-written variant of the target enum, marked with `#[custom]`.
+/// Custom variants are tried in order if the input does not match any template or only its start does.
+#[derive(Default)]
+struct CustomVariant {
+    /// A function like `CommandParse::parse_from_command` which parses the field of the variant
+    parse_with: Option<Path>,
+    /// A function like `Display::fmt` which writes the field of the variant
+    display_with: Option<Path>,
+}
+
+impl CustomVariant {
+    /// Removes the `#[custom]` attribute from `attrs` and returns its arguments
+    fn extract(attrs: &mut Vec<Attribute>) -> syn::Result<Option<Self>> {
+        let index = match attrs.iter().position(|attr| attr.path.is_ident("custom")) {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let attr = attrs.remove(index);
+
+        let mut custom = CustomVariant::default();
+        if attr.tokens.is_empty() {
+            return Ok(Some(custom));
+        }
+        let args = attr.parse_args_with(Punctuated::<PathArg, Token![,]>::parse_terminated)?;
+        for arg in args {
+            let target = if arg.name == "parse_with" {
+                &mut custom.parse_with
+            } else if arg.name == "display_with" {
+                &mut custom.display_with
+            } else {
+                return Err(syn::Error::new(
+                    arg.name.span(),
+                    "Expected `parse_with` or `display_with`",
+                ));
+            };
+            if target.is_some() {
+                return Err(syn::Error::new(arg.name.span(), "Duplicate argument"));
+            }
+            *target = Some(arg.value);
+        }
+        Ok(Some(custom))
+    }
+}
+
+/// An argument like `parse_with = my_module::parse`
+struct PathArg {
+    name: Ident,
+    value: Path,
+}
+
+impl Parse for PathArg {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        let _eq_token: Token![=] = input.parse()?;
+        let value = input.parse()?;
+        Ok(PathArg { name, value })
+    }
+}
+
+/// An argument of the `#[parser(...)]` attribute, like `target = Command`, `case_insensitive`
+/// or `include(module::Command)`
+struct MacroArg {
+    name: Ident,
+    value: Option<Expr>,
+    list: Option<Punctuated<Path, Token![,]>>,
+}
+
+impl Parse for MacroArg {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        // `crate` is a keyword, so it has to be parsed explicitly
+        let name = input.call(Ident::parse_any)?;
+        let value = if input.peek(Token![=]) {
+            let _eq_token: Token![=] = input.parse()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        let list = if value.is_none() && input.peek(token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            Some(content.parse_terminated(Path::parse)?)
+        } else {
+            None
+        };
+        Ok(MacroArg { name, value, list })
+    }
+}
+
+/// The arguments of the `#[parser(...)]` attribute
+struct MacroArgs(Punctuated<MacroArg, Token![,]>);
+
+impl Parse for MacroArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        input.parse_terminated(MacroArg::parse).map(MacroArgs)
+    }
+}
+
+/// Settings which affect the generated code
+#[derive(Debug, Clone)]
+struct CodegenConfig {
+    /// The path to the `command_parser` crate
+    krate: Path,
+    /// The character between the parts of a template
+    separator: char,
+    /// Whether literals also match if they differ in case
+    case_insensitive: bool,
+    /// How many redirects like `execute ... run <command>` may be nested, or `None` to not limit them
+    recursion_limit: Option<usize>,
+}
+
+impl Default for CodegenConfig {
+    fn default() -> Self {
+        CodegenConfig {
+            krate: parse_quote!(::command_parser),
+            separator: ' ',
+            case_insensitive: false,
+            recursion_limit: None,
+        }
+    }
+}
+
+impl CodegenConfig {
+    /// Arguments only split their input at spaces, so with another separator, each argument which
+    /// does not end its template is parsed from the text up to the next separator and has to consume all of it
+    fn parse_word(&self, parse: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        if self.separator == ' ' {
+            return parse;
+        }
+        let separator = self.separator;
+        quote! {{
+            let (word, after) = rest.split_once(#separator).unwrap_or((rest, ""));
+            let rest = word;
+            match #parse {
+                ::std::result::Result::Ok(("", value)) => ::std::result::Result::Ok((after, value)),
+                ::std::result::Result::Ok((rest, _)) | ::std::result::Result::Err(rest) => {
+                    ::std::result::Result::Err(rest)
+                }
+            }
+        }}
+    }
+
+    /// Checks that the arguments of a template can be parsed with this config
+    fn check_template(&self, template: &[ParseNode]) -> syn::Result<()> {
+        let mut errors = Errors::default();
+        for node in template {
+            if let ParseNode::Repeat { binding, .. } = node {
+                if self.separator != ' ' {
+                    errors.push(syn::Error::new(
+                        binding.span(),
+                        format!(
+                            "'{}' cannot be repeated, because repeated arguments can only be used with the default separator",
+                            member_name(binding)
+                        ),
+                    ));
+                }
+            }
+        }
+        errors.finish()
+    }
+
+    /// Wraps the expression which parses an argument of type `ty` in a `RecursionGuard` if the argument is a redirect.
+    /// Redirects are boxed arguments or arguments which contain the target enum, like `Box<Command>` or `Vec<Command>`.
+    fn guard_redirect(
+        &self,
+        ty: &Type,
+        enum_name: &Ident,
+        parse: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let limit = match self.recursion_limit {
+            Some(limit) if is_redirect(ty, enum_name) => limit,
+            _ => return parse,
+        };
+        let krate = &self.krate;
+        quote! {
+            match #krate::RecursionGuard::enter(#limit) {
+                ::std::option::Option::Some(_guard) => #parse,
+                ::std::option::Option::None => ::std::result::Result::Err(rest),
+            }
+        }
+    }
+
+    /// Converts a template node to the node which gets inserted into the parse tree
+    fn tree_node(&self, node: &ParseNode) -> ParseNode {
+        match node {
+            ParseNode::Literal(literal) if self.case_insensitive => {
+                ParseNode::Literal(literal.to_lowercase())
+            }
+            node => node.clone(),
+        }
+    }
+
+    /// The first literal of a template as it gets inserted into the parse tree
+    fn root_literal(&self, template: &[ParseNode]) -> Option<String> {
+        match template.first().map(|node| self.tree_node(node)) {
+            Some(ParseNode::Literal(literal)) => Some(literal),
+            _ => None,
+        }
+    }
+
+    /// Formats a template for `CommandSet::usage`, like `scoreboard players add <target> <value>`
+    fn usage_line(&self, template: &[ParseNode]) -> String {
+        template
+            .iter()
+            .map(|node| match node {
+                ParseNode::Literal(literal) => literal.clone(),
+                ParseNode::Function {
+                    binding: Member::Named(name),
+                    ..
+                } => format!("<{}>", name),
+                ParseNode::Function { name, .. } => {
+                    let type_name = match name {
+                        Type::Path(path) => path
+                            .path
+                            .segments
+                            .last()
+                            .map(|segment| Case::Snake.apply(&segment.ident.to_string())),
+                        _ => None,
+                    };
+                    format!("<{}>", type_name.as_deref().unwrap_or("value"))
+                }
+                ParseNode::Repeat {
+                    binding: Member::Named(name),
+                    ..
+                } => format!("<{}...>", name),
+                ParseNode::Repeat { .. } => "<values...>".to_string(),
+                _ => unreachable!("Templates only contain literals and arguments"),
+            })
+            .join(&self.separator.to_string())
+    }
+}
+
+/// Whether an argument of type `ty` parses another command of the target enum `enum_name`.
+/// This is the case for types which name the enum directly, like `Vec<Command>` or `Box<Self>`,
+/// and for boxed paths to it like `Box<super::commands::Command>`,
+/// but not for other boxed values or nested command enums like `players::Command`.
+fn is_redirect(ty: &Type, enum_name: &Ident) -> bool {
+    fn contains_enum(ty: &Type, enum_name: &Ident, is_boxed: bool) -> bool {
+        let path = match ty {
+            Type::Path(path) => &path.path,
+            _ => return false,
+        };
+        let names_enum = match path.segments.last() {
+            Some(last) if path.segments.len() == 1 || is_boxed => {
+                last.ident == *enum_name || last.ident == "Self"
+            }
+            _ => false,
+        };
+        names_enum
+            || path
+                .segments
+                .iter()
+                .any(|segment| match &segment.arguments {
+                    PathArguments::AngleBracketed(arguments) => {
+                        let is_box = segment.ident == "Box";
+                        arguments.args.iter().any(|argument| match argument {
+                            GenericArgument::Type(ty) => contains_enum(ty, enum_name, is_box),
+                            _ => false,
+                        })
+                    }
+                    _ => false,
+                })
+    }
+
+    contains_enum(ty, enum_name, false)
+}
+
+/// How many redirects can be nested in parsers which do not configure a limit
+const DEFAULT_RECURSION_LIMIT: usize = 64;
+
 #[derive(Debug)]
 struct MacroConfig {
     output_name: Ident,
+    /// The variant which holds the raw text of commands that match no template
+    fallback: Option<Ident>,
+    /// Command enums of other parsers which become variants of the target enum
+    includes: Vec<Path>,
+    codegen: CodegenConfig,
 }
 
 impl MacroConfig {
-    fn new(input: &mut ItemMod) -> syn::Result<Self> {
-        let target_enum = find_target_enum(input)?;
+    fn new(args: MacroArgs, input: &mut ItemMod) -> syn::Result<Self> {
+        let mut target = None;
+        let mut fallback = None;
+        let mut includes = Vec::new();
+        let mut codegen = CodegenConfig {
+            recursion_limit: Some(DEFAULT_RECURSION_LIMIT),
+            ..CodegenConfig::default()
+        };
+        let mut seen: Vec<String> = Vec::new();
+        for arg in args.0 {
+            let name = arg.name.to_string();
+            if seen.contains(&name) {
+                return Err(syn::Error::new(arg.name.span(), "Duplicate argument"));
+            }
+            seen.push(name.clone());
+
+            if let Some(list) = arg.list {
+                if name != "include" {
+                    return Err(syn::Error::new(
+                        arg.name.span(),
+                        "Expected no list of paths",
+                    ));
+                }
+                includes.extend(list);
+                continue;
+            }
+            match (name.as_str(), &arg.value) {
+                ("target", Some(value)) => target = Some(expect_ident(value)?),
+                ("fallback", Some(value)) => fallback = Some(expect_ident(value)?),
+                ("crate", Some(value)) => codegen.krate = expect_path(value)?,
+                ("separator", Some(value)) => {
+                    codegen.separator = match value {
+                        Expr::Lit(ExprLit {
+                            lit: Lit::Char(separator),
+                            ..
+                        }) => separator.value(),
+                        _ => return Err(syn::Error::new(value.span(), "Expected a character")),
+                    }
+                }
+                ("recursion_limit", Some(value)) => {
+                    codegen.recursion_limit = match value {
+                        Expr::Lit(ExprLit {
+                            lit: Lit::Int(limit),
+                            ..
+                        }) if limit.base10_parse::<usize>()? > 0 => Some(limit.base10_parse()?),
+                        _ => {
+                            return Err(syn::Error::new(
+                                value.span(),
+                                "Expected an integer greater than zero",
+                            ))
+                        }
+                    }
+                }
+                ("case_insensitive", None) => codegen.case_insensitive = true,
+                ("include", _) => {
+                    return Err(syn::Error::new(
+                        arg.name.span(),
+                        "Expected a list like `include(module::Command)`",
+                    ))
+                }
+                ("target" | "fallback" | "crate" | "separator" | "recursion_limit", None) => {
+                    return Err(syn::Error::new(
+                        arg.name.span(),
+                        format!("Expected a value like `{} = ...`", name),
+                    ))
+                }
+                ("case_insensitive", Some(value)) => {
+                    return Err(syn::Error::new(value.span(), "Expected no value"))
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        arg.name.span(),
+                        "Unknown argument, expected one of `target`, `fallback`, `include`, `crate`, `separator`, `case_insensitive` or `recursion_limit`",
+                    ))
+                }
+            }
+        }
+
+        let target_enum = find_target_enum(input, target.as_ref())?;
         Ok(MacroConfig {
             output_name: target_enum.ident.clone(),
+            fallback,
+            includes,
+            codegen,
         })
     }
 }
+
+fn expect_path(value: &Expr) -> syn::Result<Path> {
+    match value {
+        Expr::Path(path) => Ok(path.path.clone()),
+        _ => Err(syn::Error::new(value.span(), "Expected a path")),
+    }
+}
+
+fn expect_ident(value: &Expr) -> syn::Result<Ident> {
+    match value {
+        Expr::Path(path) => path.path.get_ident().cloned(),
+        _ => None,
+    }
+    .ok_or_else(|| syn::Error::new(value.span(), "Expected a name"))
+}
+
+#[cfg(test)]
+mod test {
+    use proc_macro2::{Ident, Span};
+    use syn::{parse_quote, LitStr, Type};
+
+    use super::{is_redirect, split_template};
+
+    #[test]
+    fn test_split_template() {
+        let template: LitStr = parse_quote!(" scoreboard  players\tadd $target ");
+        let parts: Vec<String> = split_template(&template)
+            .into_iter()
+            .map(|part| part.text)
+            .collect();
+        assert_eq!(parts, vec!["scoreboard", "players", "add", "$target"]);
+
+        // Outside of a compiler, spans cannot point into the literal, so the parts know their column
+        let template: LitStr = parse_quote!("tp  $target $0");
+        let columns: Vec<Option<usize>> = split_template(&template)
+            .into_iter()
+            .map(|part| part.column)
+            .collect();
+        assert_eq!(columns, vec![Some(1), Some(5), Some(13)]);
+
+        let template: LitStr = parse_quote!("");
+        assert!(split_template(&template).is_empty());
+    }
+
+    #[test]
+    fn test_is_redirect() {
+        let command = Ident::new("Command", Span::call_site());
+        let redirects: [Type; 4] = [
+            parse_quote!(Box<Command>),
+            parse_quote!(Box<super::commands::Command>),
+            parse_quote!(Vec<Command>),
+            parse_quote!(Option<Box<Self>>),
+        ];
+        for ty in &redirects {
+            assert!(is_redirect(ty, &command));
+        }
+        let arguments: [Type; 5] = [
+            parse_quote!(Box<String>),
+            parse_quote!(Option<Box<i32>>),
+            parse_quote!(players::Command),
+            parse_quote!(Vec<super::modifiers::Modifier>),
+            parse_quote!(String),
+        ];
+        for ty in &arguments {
+            assert!(!is_redirect(ty, &command));
+        }
+    }
+}
@@ -1,7 +1,6 @@
 use std::collections::HashMap;
 
-use proc_macro2::Ident;
-use syn::{Expr, Member, Type};
+use syn::{Expr, Member, Path, Type};
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct ParseTree {
@@ -12,7 +11,8 @@ pub struct ParseTree {
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum ParseNode {
     EndOfInput {
-        struct_name: Ident,
+        /// The path used to construct the parsed value, like `Foo` or `Self::Foo`
+        struct_name: Path,
         defaults: HashMap<Member, Expr>,
         members: Vec<Member>,
     },
@@ -21,6 +21,15 @@ pub enum ParseNode {
     Function {
         name: Type,
         binding: Member,
+        /// A function which replaces `CommandParse::parse_from_command`
+        parse_with: Option<Path>,
+        /// A function which replaces `Display::fmt`
+        display_with: Option<Path>,
+    },
+    /// An argument like `$modifiers*`, which is parsed as often as possible into a `Vec`
+    Repeat {
+        name: Type,
+        binding: Member,
     },
 }
 
@@ -35,7 +44,7 @@ impl ParseTree {
     pub fn insert(
         &mut self,
         items: impl Iterator<Item = ParseNode>,
-        struct_name: Ident,
+        struct_name: Path,
         defaults: HashMap<Member, Expr>,
         members: Vec<Member>,
     ) {
@@ -70,6 +79,7 @@ mod test {
     use std::collections::HashMap;
 
     use proc_macro2::{Ident, Span};
+    use syn::Path;
 
     use crate::parse_tree::ParseNode;
 
@@ -79,7 +89,7 @@ mod test {
     fn test_build_tree() {
         let defaults = HashMap::new();
         let idents = Vec::new();
-        let struct_name = Ident::new("Foo", Span::call_site());
+        let struct_name = Path::from(Ident::new("Foo", Span::call_site()));
 
         let mut tree = ParseTree::new();
 
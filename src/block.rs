@@ -0,0 +1,250 @@
+use std::fmt;
+
+use crate::{
+    is_unquoted_char,
+    nbt::{parse_quoted_string, write_compound, write_quoted},
+    strip_separator, take_while, CommandParse, Nbt, NbtCompound, ResourceLocation, TagOrId,
+};
+
+/// The properties of a block, like `[facing=north,waterlogged=false]`, in the order they were written
+pub type BlockProperties = Vec<(String, String)>;
+
+/// A block with optional properties and nbt, like `minecraft:chest[facing=north]{Items:[]}`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockState {
+    pub id: ResourceLocation,
+    pub properties: BlockProperties,
+    pub nbt: Option<NbtCompound>,
+}
+
+impl fmt::Display for BlockState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)?;
+        write_properties_and_nbt(f, &self.properties, &self.nbt)
+    }
+}
+
+impl CommandParse for BlockState {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, id) = ResourceLocation::parse_prefix(value)?;
+        let (rest, properties, nbt) = parse_properties_and_nbt(rest)?;
+        let rest = strip_separator(rest)?;
+        Ok((
+            rest,
+            BlockState {
+                id,
+                properties,
+                nbt,
+            },
+        ))
+    }
+}
+
+/// A block or block tag with optional properties and nbt that a block has to match,
+/// like `#minecraft:logs[axis=y]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockPredicate {
+    pub block: TagOrId,
+    pub properties: BlockProperties,
+    pub nbt: Option<NbtCompound>,
+}
+
+impl fmt::Display for BlockPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.block)?;
+        write_properties_and_nbt(f, &self.properties, &self.nbt)
+    }
+}
+
+impl CommandParse for BlockPredicate {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, block) = TagOrId::parse_prefix(value)?;
+        let (rest, properties, nbt) = parse_properties_and_nbt(rest)?;
+        let rest = strip_separator(rest)?;
+        Ok((
+            rest,
+            BlockPredicate {
+                block,
+                properties,
+                nbt,
+            },
+        ))
+    }
+}
+
+fn write_properties_and_nbt(
+    f: &mut fmt::Formatter<'_>,
+    properties: &[(String, String)],
+    nbt: &Option<NbtCompound>,
+) -> fmt::Result {
+    if !properties.is_empty() {
+        write!(f, "[")?;
+        for (index, (name, value)) in properties.iter().enumerate() {
+            if index > 0 {
+                write!(f, ",")?;
+            }
+            write_word(f, name)?;
+            write!(f, "=")?;
+            write_word(f, value)?;
+        }
+        write!(f, "]")?;
+    }
+    if let Some(nbt) = nbt {
+        write_compound(f, nbt)?;
+    }
+    Ok(())
+}
+
+/// Writes a property name or value, quoting it if it could not be parsed unquoted
+fn write_word(f: &mut fmt::Formatter<'_>, word: &str) -> fmt::Result {
+    if !word.is_empty() && word.chars().all(is_unquoted_char) {
+        write!(f, "{}", word)
+    } else {
+        write_quoted(f, word)
+    }
+}
+
+/// Parses the optional properties and nbt following a block id
+fn parse_properties_and_nbt(
+    value: &str,
+) -> Result<(&str, BlockProperties, Option<NbtCompound>), &str> {
+    let (rest, properties) = if value.starts_with('[') {
+        parse_properties(value)?
+    } else {
+        (value, Vec::new())
+    };
+
+    let (rest, nbt) = if rest.starts_with('{') {
+        // Point at the opening brace if the compound is never closed
+        let (rest, nbt) =
+            Nbt::parse_compound(rest)
+                .map_err(|error| if error.is_empty() { rest } else { error })?;
+        (rest, Some(nbt))
+    } else {
+        (rest, None)
+    };
+    Ok((rest, properties, nbt))
+}
+
+/// Parses a property list including its brackets.
+/// Fails at the opening bracket if the list is never closed and at the name of duplicated properties.
+fn parse_properties<'a>(value: &'a str) -> Result<(&'a str, BlockProperties), &'a str> {
+    let unterminated = |rest: &'a str| if rest.is_empty() { value } else { rest };
+
+    let mut properties = BlockProperties::new();
+    let mut rest = value.strip_prefix('[').ok_or(value)?.trim_start();
+    if let Some(rest) = rest.strip_prefix(']') {
+        return Ok((rest, properties));
+    }
+
+    loop {
+        let (after_name, name) = parse_word(rest).map_err(unterminated)?;
+        if properties.iter().any(|(existing, _)| *existing == name) {
+            return Err(rest);
+        }
+
+        let after_eq = after_name.trim_start();
+        let after_eq = after_eq
+            .strip_prefix('=')
+            .ok_or_else(|| unterminated(after_eq))?
+            .trim_start();
+        let (after_value, property_value) = parse_word(after_eq).map_err(unterminated)?;
+        properties.push((name, property_value));
+
+        let after_value = after_value.trim_start();
+        if let Some(next) = after_value.strip_prefix(',') {
+            rest = next.trim_start();
+        } else if let Some(rest) = after_value.strip_prefix(']') {
+            return Ok((rest, properties));
+        } else {
+            return Err(unterminated(after_value));
+        }
+    }
+}
+
+/// Parses a quoted or unquoted property name or value
+fn parse_word(value: &str) -> Result<(&str, String), &str> {
+    if value.starts_with('"') || value.starts_with('\'') {
+        parse_quoted_string(value)
+    } else {
+        let (rest, word) = take_while(value, is_unquoted_char);
+        if word.is_empty() {
+            return Err(value);
+        }
+        Ok((rest, word.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BlockPredicate, BlockState};
+    use crate::{CommandParse, Nbt, NbtCompound, ResourceLocation, TagOrId};
+
+    #[test]
+    fn test_block_state() {
+        let (rest, block) =
+            BlockState::parse_from_command("minecraft:chest[facing=north]{Items:[]} replace")
+                .unwrap();
+        assert_eq!(rest, "replace");
+
+        let mut nbt = NbtCompound::new();
+        nbt.insert("Items".to_string(), Nbt::List(Vec::new()));
+        assert_eq!(
+            block,
+            BlockState {
+                id: ResourceLocation::new("chest").unwrap(),
+                properties: vec![("facing".to_string(), "north".to_string())],
+                nbt: Some(nbt),
+            }
+        );
+        assert_eq!(block.to_string(), "minecraft:chest[facing=north]{Items:[]}");
+
+        let (_, block) = BlockState::parse_from_command("stone").unwrap();
+        assert_eq!(block.to_string(), "minecraft:stone");
+    }
+
+    #[test]
+    fn test_block_predicate() {
+        let (rest, block) = BlockPredicate::parse_from_command("#logs[ axis = y ]").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            block.block,
+            TagOrId::Tag(ResourceLocation::new("logs").unwrap())
+        );
+        assert_eq!(block.to_string(), "#minecraft:logs[axis=y]");
+    }
+
+    #[test]
+    fn test_quoted_properties() {
+        let input = "minecraft:stone[\"a b\"=\"x y\",c='say \"hi\"']";
+        let (_, block) = BlockState::parse_from_command(input).unwrap();
+        assert_eq!(block.properties[0], ("a b".to_string(), "x y".to_string()));
+        assert_eq!(block.to_string(), input);
+
+        let (_, block) = BlockState::parse_from_command("stone[a=\"x\"]").unwrap();
+        assert_eq!(block.to_string(), "minecraft:stone[a=x]");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            BlockState::parse_from_command("stone[facing=north"),
+            Err("[facing=north")
+        );
+        assert_eq!(
+            BlockState::parse_from_command("stone[facing="),
+            Err("[facing=")
+        );
+        assert_eq!(
+            BlockState::parse_from_command("stone[a=b,a=c]"),
+            Err("a=c]")
+        );
+        assert_eq!(
+            BlockState::parse_from_command("stone[a=b;c=d]"),
+            Err(";c=d]")
+        );
+        assert_eq!(BlockState::parse_from_command("stone{a:1"), Err("{a:1"));
+        assert_eq!(BlockState::parse_from_command("stone[a=b,]"), Err("]"));
+        assert_eq!(BlockState::parse_from_command("#logs"), Err("#logs"));
+    }
+}
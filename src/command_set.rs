@@ -0,0 +1,111 @@
+use crate::CommandParse;
+
+/// A set of commands generated by the `parser` macro
+pub trait CommandSet: CommandParse {
+    /// The character between the words of a command
+    const SEPARATOR: char;
+
+    /// The literals which commands of this set start with.
+    /// Command sets which are included in another set must not share any of these,
+    /// as they are tried in order and the first one which parses a command is used.
+    const ROOT_LITERALS: &'static [&'static str];
+
+    /// One line per command template, like `scoreboard players add <target> <value>`
+    fn usage() -> Vec<String>;
+
+    /// Returns the candidates for the last, possibly incomplete word of `input`.
+    /// Literals are completed, while arguments are returned as their placeholder like `<target>`.
+    /// Arguments are assumed to be single words.
+    fn completions(input: &str) -> Vec<String> {
+        let mut words: Vec<&str> = input.split(Self::SEPARATOR).collect();
+        let partial = words.pop().unwrap_or_default();
+
+        let mut completions = Vec::new();
+        for line in Self::usage() {
+            let mut parts = line.split(Self::SEPARATOR);
+            let matches = words.iter().all(|word| {
+                parts
+                    .next()
+                    .map_or(false, |part| part == *word || is_placeholder(part))
+            });
+            let next = match parts.next() {
+                Some(next) if matches => next,
+                _ => continue,
+            };
+            if (next.starts_with(partial) || is_placeholder(next))
+                && !completions.iter().any(|completion| completion == next)
+            {
+                completions.push(next.to_string());
+            }
+        }
+        completions
+    }
+}
+
+fn is_placeholder(part: &str) -> bool {
+    part.starts_with('<') && part.ends_with('>')
+}
+
+/// Concatenates the root literals of several command sets at compile time
+#[doc(hidden)]
+pub const fn concat_literals<const N: usize>(parts: &[&[&'static str]]) -> [&'static str; N] {
+    let mut result = [""; N];
+    let mut index = 0;
+    let mut part = 0;
+    while part < parts.len() {
+        let mut literal = 0;
+        while literal < parts[part].len() {
+            result[index] = parts[part][literal];
+            index += 1;
+            literal += 1;
+        }
+        part += 1;
+    }
+    result
+}
+
+/// Whether two lists of root literals share a literal
+#[doc(hidden)]
+pub const fn literals_overlap(a: &[&str], b: &[&str]) -> bool {
+    let mut i = 0;
+    while i < a.len() {
+        let mut j = 0;
+        while j < b.len() {
+            if str_eq(a[i], b[j]) {
+                return true;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    false
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::{concat_literals, literals_overlap};
+
+    #[test]
+    fn test_literals() {
+        const LITERALS: [&str; 3] = concat_literals(&[&["say", "tell"], &["setblock"]]);
+        assert_eq!(LITERALS, ["say", "tell", "setblock"]);
+        assert!(literals_overlap(&LITERALS, &["fill", "tell"]));
+        assert!(!literals_overlap(&LITERALS, &["fill", "tel", "sayy"]));
+        assert!(!literals_overlap(&LITERALS, &[]));
+    }
+}
@@ -0,0 +1,335 @@
+use std::fmt;
+
+use crate::{parse_str, CommandParse};
+
+/// A number as used in coordinates, which keeps the text it was parsed from
+///
+/// Vanilla treats whole numbers differently depending on whether they were written with a
+/// decimal point (`1` is centered on the block, `1.0` is not), and commands should not change
+/// when they are written back, so `-.5` or `1.50` are displayed exactly as they were parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoordinateValue {
+    value: f64,
+    text: String,
+}
+
+impl CoordinateValue {
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Whether the number was written without a decimal point
+    pub fn is_integer(&self) -> bool {
+        !self.text.contains('.')
+    }
+
+    /// The number as an `i32`, if it was written without a decimal point and is in range
+    pub fn as_integer(&self) -> Option<i32> {
+        if self.is_integer() {
+            self.text.parse().ok()
+        } else {
+            None
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        let is_number = value
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '.' || c == '-');
+        if value.is_empty() || !is_number {
+            return None;
+        }
+
+        // Integers outside of the `i32` range are still valid, as vanilla reads them as doubles
+        value.parse().ok().map(|parsed| CoordinateValue {
+            value: parsed,
+            text: value.to_string(),
+        })
+    }
+}
+
+impl From<i32> for CoordinateValue {
+    fn from(value: i32) -> Self {
+        CoordinateValue {
+            value: value.into(),
+            text: value.to_string(),
+        }
+    }
+}
+
+impl From<f64> for CoordinateValue {
+    /// Creates a number with a decimal point, like `1.0` for `1.0`
+    fn from(value: f64) -> Self {
+        let mut text = value.to_string();
+        if !text.contains('.') {
+            text.push_str(".0");
+        }
+        CoordinateValue { value, text }
+    }
+}
+
+impl fmt::Display for CoordinateValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// A coordinate which is either absolute (`1.5`) or relative to the executing position (`~`, `~1.5`)
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorldCoordinate {
+    Absolute(CoordinateValue),
+    Relative(Option<CoordinateValue>),
+}
+
+impl WorldCoordinate {
+    fn is_integer(&self) -> bool {
+        match self {
+            WorldCoordinate::Absolute(val) => val.as_integer().is_some(),
+            WorldCoordinate::Relative(_) => true,
+        }
+    }
+}
+
+impl fmt::Display for WorldCoordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorldCoordinate::Absolute(val) => write!(f, "{}", val),
+            WorldCoordinate::Relative(None) => write!(f, "~"),
+            WorldCoordinate::Relative(Some(val)) => write!(f, "~{}", val),
+        }
+    }
+}
+
+/// A coordinate relative to the executing rotation (`^`, `^1.5`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalCoordinate(pub Option<CoordinateValue>);
+
+impl fmt::Display for LocalCoordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            None => write!(f, "^"),
+            Some(val) => write!(f, "^{}", val),
+        }
+    }
+}
+
+enum Component {
+    World(WorldCoordinate),
+    Local(LocalCoordinate),
+}
+
+/// Parses a single coordinate and returns a tuple of `(rest, component)`
+fn parse_component(value: &str) -> Result<(&str, Component), &str> {
+    let (rest, word) = parse_str(value);
+
+    let parse_offset = |offset: &str| {
+        if offset.is_empty() {
+            Some(None)
+        } else {
+            CoordinateValue::parse(offset).map(Some)
+        }
+    };
+
+    let component = if let Some(offset) = word.strip_prefix('^') {
+        parse_offset(offset).map(|val| Component::Local(LocalCoordinate(val)))
+    } else if let Some(offset) = word.strip_prefix('~') {
+        parse_offset(offset).map(|val| Component::World(WorldCoordinate::Relative(val)))
+    } else {
+        CoordinateValue::parse(word).map(|val| Component::World(WorldCoordinate::Absolute(val)))
+    };
+
+    component.map(|component| (rest, component)).ok_or(value)
+}
+
+/// Parses two world coordinates, as local coordinates are not allowed for two-dimensional values
+fn parse_world_pair(value: &str) -> Result<(&str, WorldCoordinate, WorldCoordinate), &str> {
+    let (rest_a, a) = parse_component(value)?;
+    let (rest, b) = parse_component(rest_a)?;
+    match (a, b) {
+        (Component::World(a), Component::World(b)) => Ok((rest, a, b)),
+        (Component::World(_), Component::Local(_)) => Err(rest_a),
+        (Component::Local(_), _) => Err(value),
+    }
+}
+
+/// A position like `1 ~2 3.5` or `^ ^ ^1`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Vec3 {
+    World {
+        x: WorldCoordinate,
+        y: WorldCoordinate,
+        z: WorldCoordinate,
+    },
+    Local {
+        left: LocalCoordinate,
+        up: LocalCoordinate,
+        forwards: LocalCoordinate,
+    },
+}
+
+impl fmt::Display for Vec3 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Vec3::World { x, y, z } => write!(f, "{} {} {}", x, y, z),
+            Vec3::Local { left, up, forwards } => write!(f, "{} {} {}", left, up, forwards),
+        }
+    }
+}
+
+impl CommandParse for Vec3 {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        let (rest_x, x) = parse_component(value)?;
+        let (rest_y, y) = parse_component(rest_x)?;
+        let (rest, z) = parse_component(rest_y)?;
+
+        let vec = match (x, y, z) {
+            (Component::World(x), Component::World(y), Component::World(z)) => {
+                Vec3::World { x, y, z }
+            }
+            (Component::Local(left), Component::Local(up), Component::Local(forwards)) => {
+                Vec3::Local { left, up, forwards }
+            }
+            // Local and world coordinates cannot be mixed
+            (Component::World(_), Component::World(_), _)
+            | (Component::Local(_), Component::Local(_), _) => return Err(rest_y),
+            (_, _, _) => return Err(rest_x),
+        };
+        Ok((rest, vec))
+    }
+}
+
+/// A block position, which is a [`Vec3`] whose absolute coordinates must be whole numbers
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockPos(pub Vec3);
+
+impl fmt::Display for BlockPos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl CommandParse for BlockPos {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, vec) = Vec3::parse_from_command(value)?;
+        if let Vec3::World { x, y, z } = &vec {
+            if !(x.is_integer() && y.is_integer() && z.is_integer()) {
+                return Err(value);
+            }
+        }
+        Ok((rest, BlockPos(vec)))
+    }
+}
+
+/// A horizontal position like `1 ~2`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vec2 {
+    pub x: WorldCoordinate,
+    pub z: WorldCoordinate,
+}
+
+impl fmt::Display for Vec2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.x, self.z)
+    }
+}
+
+impl CommandParse for Vec2 {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, x, z) = parse_world_pair(value)?;
+        Ok((rest, Vec2 { x, z }))
+    }
+}
+
+/// A rotation given as `yaw pitch`, for example `~90 0`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rotation {
+    pub yaw: WorldCoordinate,
+    pub pitch: WorldCoordinate,
+}
+
+impl fmt::Display for Rotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.yaw, self.pitch)
+    }
+}
+
+impl CommandParse for Rotation {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, yaw, pitch) = parse_world_pair(value)?;
+        Ok((rest, Rotation { yaw, pitch }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        BlockPos, CoordinateValue, LocalCoordinate, Rotation, Vec2, Vec3, WorldCoordinate,
+    };
+    use crate::CommandParse;
+
+    #[test]
+    fn test_vec3() {
+        assert_eq!(
+            Vec3::parse_from_command("1 ~ ~-2.5 foo"),
+            Ok((
+                "foo",
+                Vec3::World {
+                    x: WorldCoordinate::Absolute(CoordinateValue::from(1)),
+                    y: WorldCoordinate::Relative(None),
+                    z: WorldCoordinate::Relative(Some(CoordinateValue::from(-2.5))),
+                }
+            ))
+        );
+        assert_eq!(
+            Vec3::parse_from_command("^ ^ ^2"),
+            Ok((
+                "",
+                Vec3::Local {
+                    left: LocalCoordinate(None),
+                    up: LocalCoordinate(None),
+                    forwards: LocalCoordinate(Some(CoordinateValue::from(2))),
+                }
+            ))
+        );
+        assert_eq!(Vec3::parse_from_command("^ ~ ^"), Err("~ ^"));
+        assert_eq!(Vec3::parse_from_command("1 2 ^3"), Err("^3"));
+        assert_eq!(Vec3::parse_from_command("1 2"), Err(""));
+        assert_eq!(Vec3::parse_from_command("1 2 3e5"), Err("3e5"));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for input in &["1 ~ ~-2.5", "1.0 -0.5 ~0", "^ ^1 ^-.5", "~.5 1 2"] {
+            let (_, vec) = Vec3::parse_from_command(input).unwrap();
+            let (_, reparsed) = Vec3::parse_from_command(&vec.to_string()).unwrap();
+            assert_eq!(vec, reparsed);
+        }
+        let (_, vec) = Vec3::parse_from_command("1.0 ~ ~0").unwrap();
+        assert_eq!(vec.to_string(), "1.0 ~ ~0");
+        for input in &["^ ^-.5 ^1.50", "~-.0 007 1.", "3000000000 ~ 0"] {
+            let (_, vec) = Vec3::parse_from_command(input).unwrap();
+            assert_eq!(vec.to_string(), *input);
+        }
+    }
+
+    #[test]
+    fn test_block_pos() {
+        assert!(BlockPos::parse_from_command("1 ~0.5 3").is_ok());
+        assert!(BlockPos::parse_from_command("^ ^ ^0.5").is_ok());
+        assert_eq!(BlockPos::parse_from_command("1 2.5 3"), Err("1 2.5 3"));
+        assert_eq!(
+            BlockPos::parse_from_command("3000000000 0 0"),
+            Err("3000000000 0 0")
+        );
+    }
+
+    #[test]
+    fn test_vec2_and_rotation() {
+        let (_, vec) = Vec2::parse_from_command("~1 2").unwrap();
+        assert_eq!(vec.to_string(), "~1 2");
+        assert_eq!(Vec2::parse_from_command("^ ^"), Err("^ ^"));
+        assert_eq!(Rotation::parse_from_command("~ ^"), Err("^"));
+        let (_, rotation) = Rotation::parse_from_command("~90 0.0").unwrap();
+        assert_eq!(rotation.to_string(), "~90 0.0");
+    }
+}
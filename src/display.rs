@@ -0,0 +1,99 @@
+use std::fmt;
+
+/// Writes the values of a repeated template argument like `$modifiers*`.
+/// If `leading` is set, every value is preceded by the separator, and if `trailing` is set,
+/// every value is followed by it. Otherwise the values are joined by the separator.
+/// This way, no values write nothing at all.
+#[doc(hidden)]
+pub struct DisplayRepeated<'a, T> {
+    values: &'a [T],
+    separator: char,
+    leading: bool,
+    trailing: bool,
+}
+
+#[doc(hidden)]
+pub fn display_repeated<T>(
+    values: &[T],
+    separator: char,
+    leading: bool,
+    trailing: bool,
+) -> DisplayRepeated<'_, T> {
+    DisplayRepeated {
+        values,
+        separator,
+        leading,
+        trailing,
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for DisplayRepeated<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, value) in self.values.iter().enumerate() {
+            if self.leading || (index > 0 && !self.trailing) {
+                write!(f, "{}", self.separator)?;
+            }
+            write!(f, "{}", value)?;
+            if self.trailing {
+                write!(f, "{}", self.separator)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes a value with a function like `Display::fmt`, for fields with `#[arg(display_with = path)]`
+#[doc(hidden)]
+pub struct DisplayWith<'a, T> {
+    value: &'a T,
+    display: fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+}
+
+#[doc(hidden)]
+pub fn display_with<T>(
+    value: &T,
+    display: fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+) -> DisplayWith<'_, T> {
+    DisplayWith { value, display }
+}
+
+impl<T> fmt::Display for DisplayWith<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.display)(self.value, f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fmt;
+
+    use super::{display_repeated, display_with};
+
+    #[test]
+    fn test_display_repeated() {
+        assert_eq!(
+            display_repeated(&[1, 2], ' ', false, true).to_string(),
+            "1 2 "
+        );
+        assert_eq!(
+            display_repeated(&[1, 2], ' ', true, false).to_string(),
+            " 1 2"
+        );
+        assert_eq!(
+            display_repeated(&[1, 2], ' ', false, false).to_string(),
+            "1 2"
+        );
+        assert_eq!(
+            display_repeated::<i32>(&[], ' ', true, false).to_string(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_display_with() {
+        fn hex(value: &u32, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{:#x}", value)
+        }
+        assert_eq!(display_with(&255, hex).to_string(), "0xff");
+    }
+}
@@ -0,0 +1,344 @@
+use std::fmt;
+
+use crate::{
+    nbt::write_compound, strip_separator, CommandParse, Nbt, NbtCompound, ResourceLocation, TagOrId,
+};
+
+/// A component of an [`ItemStack`], like `damage=3` or `!food`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemComponent {
+    Set(ResourceLocation, Nbt),
+    /// Removes a default component of the item
+    Removed(ResourceLocation),
+}
+
+impl ItemComponent {
+    pub fn name(&self) -> &ResourceLocation {
+        match self {
+            ItemComponent::Set(name, _) | ItemComponent::Removed(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for ItemComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ItemComponent::Set(name, value) => write!(f, "{}={}", name, value),
+            ItemComponent::Removed(name) => write!(f, "!{}", name),
+        }
+    }
+}
+
+/// An item with optional components and legacy nbt, like `diamond_sword[damage=3]` or `stone{foo:1b}`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemStack {
+    pub id: ResourceLocation,
+    pub components: Vec<ItemComponent>,
+    pub nbt: Option<NbtCompound>,
+}
+
+impl fmt::Display for ItemStack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)?;
+        write_list(f, &self.components)?;
+        write_nbt(f, &self.nbt)
+    }
+}
+
+impl CommandParse for ItemStack {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, id) = ResourceLocation::parse_prefix(value)?;
+        let (rest, components) = if rest.starts_with('[') {
+            let mut names = Vec::new();
+            parse_list(rest, |element| {
+                let (rest, component) = parse_component(element)?;
+                // Components may only be specified once
+                if names.contains(component.name()) {
+                    return Err(element);
+                }
+                names.push(component.name().clone());
+                Ok((rest, component))
+            })?
+        } else {
+            (rest, Vec::new())
+        };
+
+        let (rest, nbt) = parse_nbt(rest)?;
+        let rest = strip_separator(rest)?;
+        Ok((
+            rest,
+            ItemStack {
+                id,
+                components,
+                nbt,
+            },
+        ))
+    }
+}
+
+/// The check an [`ItemTest`] performs on a component
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemTestKind {
+    /// The component exists, like `damage`
+    Exists,
+    /// The component equals a value, like `damage=3`
+    Equals(Nbt),
+    /// The item matches a sub-predicate, like `damage~{damage:{min:3}}`
+    Matches(Nbt),
+}
+
+/// A single test of an [`ItemPredicate`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemTest {
+    pub negated: bool,
+    pub name: ResourceLocation,
+    pub kind: ItemTestKind,
+}
+
+impl fmt::Display for ItemTest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negated {
+            write!(f, "!")?;
+        }
+        write!(f, "{}", self.name)?;
+        match &self.kind {
+            ItemTestKind::Exists => Ok(()),
+            ItemTestKind::Equals(value) => write!(f, "={}", value),
+            ItemTestKind::Matches(value) => write!(f, "~{}", value),
+        }
+    }
+}
+
+/// Alternative tests separated by `|`, of which any one has to pass
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemTestAlternatives(pub Vec<ItemTest>);
+
+impl fmt::Display for ItemTestAlternatives {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, test) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, "|")?;
+            }
+            write!(f, "{}", test)?;
+        }
+        Ok(())
+    }
+}
+
+/// A predicate which items have to match, like `#swords`, `*[damage=0]` or `stick{foo:1b}`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemPredicate {
+    /// The item or item tag to match, `None` for any item (`*`)
+    pub item: Option<TagOrId>,
+    /// Tests which all have to pass
+    pub tests: Vec<ItemTestAlternatives>,
+    pub nbt: Option<NbtCompound>,
+}
+
+impl fmt::Display for ItemPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.item {
+            Some(item) => write!(f, "{}", item)?,
+            None => write!(f, "*")?,
+        }
+        write_list(f, &self.tests)?;
+        write_nbt(f, &self.nbt)
+    }
+}
+
+impl CommandParse for ItemPredicate {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, item) = match value.strip_prefix('*') {
+            Some(rest) => (rest, None),
+            None => {
+                let (rest, item) = TagOrId::parse_prefix(value)?;
+                (rest, Some(item))
+            }
+        };
+        let (rest, tests) = if rest.starts_with('[') {
+            parse_list(rest, parse_alternatives)?
+        } else {
+            (rest, Vec::new())
+        };
+        let (rest, nbt) = parse_nbt(rest)?;
+        let rest = strip_separator(rest)?;
+        Ok((rest, ItemPredicate { item, tests, nbt }))
+    }
+}
+
+fn write_list<T: fmt::Display>(f: &mut fmt::Formatter<'_>, values: &[T]) -> fmt::Result {
+    if values.is_empty() {
+        return Ok(());
+    }
+    write!(f, "[")?;
+    for (index, value) in values.iter().enumerate() {
+        if index > 0 {
+            write!(f, ",")?;
+        }
+        write!(f, "{}", value)?;
+    }
+    write!(f, "]")
+}
+
+fn write_nbt(f: &mut fmt::Formatter<'_>, nbt: &Option<NbtCompound>) -> fmt::Result {
+    match nbt {
+        Some(nbt) => write_compound(f, nbt),
+        None => Ok(()),
+    }
+}
+
+/// Parses the optional legacy nbt of an item, pointing at the opening brace if it is never closed
+fn parse_nbt(value: &str) -> Result<(&str, Option<NbtCompound>), &str> {
+    if !value.starts_with('{') {
+        return Ok((value, None));
+    }
+    let (rest, nbt) =
+        Nbt::parse_compound(value).map_err(|error| if error.is_empty() { value } else { error })?;
+    Ok((rest, Some(nbt)))
+}
+
+/// Parses a comma separated list including its brackets.
+/// Fails at the opening bracket if the list is never closed.
+fn parse_list<'a, T>(
+    value: &'a str,
+    mut parse_element: impl FnMut(&'a str) -> Result<(&'a str, T), &'a str>,
+) -> Result<(&'a str, Vec<T>), &'a str> {
+    let unterminated = |rest: &'a str| if rest.is_empty() { value } else { rest };
+
+    let mut elements = Vec::new();
+    let mut rest = value.strip_prefix('[').ok_or(value)?.trim_start();
+    if let Some(rest) = rest.strip_prefix(']') {
+        return Ok((rest, elements));
+    }
+
+    loop {
+        let (after_element, element) = parse_element(rest).map_err(unterminated)?;
+        elements.push(element);
+
+        let after_element = after_element.trim_start();
+        if let Some(next) = after_element.strip_prefix(',') {
+            rest = next.trim_start();
+        } else if let Some(rest) = after_element.strip_prefix(']') {
+            return Ok((rest, elements));
+        } else {
+            return Err(unterminated(after_element));
+        }
+    }
+}
+
+fn parse_component(value: &str) -> Result<(&str, ItemComponent), &str> {
+    if let Some(removed) = value.strip_prefix('!') {
+        let (rest, name) = ResourceLocation::parse_prefix(removed.trim_start())?;
+        return Ok((rest, ItemComponent::Removed(name)));
+    }
+
+    let (rest, name) = ResourceLocation::parse_prefix(value)?;
+    let rest = rest.trim_start();
+    let rest = rest.strip_prefix('=').ok_or(rest)?.trim_start();
+    let (rest, value) = Nbt::parse_value(rest)?;
+    Ok((rest, ItemComponent::Set(name, value)))
+}
+
+fn parse_alternatives(value: &str) -> Result<(&str, ItemTestAlternatives), &str> {
+    let mut tests = Vec::new();
+    let mut rest = value;
+    loop {
+        let (after_test, test) = parse_test(rest)?;
+        tests.push(test);
+
+        let after_test = after_test.trim_start();
+        match after_test.strip_prefix('|') {
+            Some(next) => rest = next.trim_start(),
+            None => return Ok((after_test, ItemTestAlternatives(tests))),
+        }
+    }
+}
+
+fn parse_test(value: &str) -> Result<(&str, ItemTest), &str> {
+    let (rest, negated) = match value.strip_prefix('!') {
+        Some(rest) => (rest.trim_start(), true),
+        None => (value, false),
+    };
+    let (rest, name) = ResourceLocation::parse_prefix(rest)?;
+
+    let after_name = rest.trim_start();
+    let (rest, kind) = if let Some(rest) = after_name.strip_prefix('=') {
+        let (rest, value) = Nbt::parse_value(rest.trim_start())?;
+        (rest, ItemTestKind::Equals(value))
+    } else if let Some(rest) = after_name.strip_prefix('~') {
+        let (rest, value) = Nbt::parse_value(rest.trim_start())?;
+        (rest, ItemTestKind::Matches(value))
+    } else {
+        (rest, ItemTestKind::Exists)
+    };
+    Ok((
+        rest,
+        ItemTest {
+            negated,
+            name,
+            kind,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ItemComponent, ItemPredicate, ItemStack, ItemTest, ItemTestKind};
+    use crate::{CommandParse, Nbt, ResourceLocation, TagOrId};
+
+    #[test]
+    fn test_item_stack() {
+        let (rest, item) =
+            ItemStack::parse_from_command("diamond_sword[damage=3, !food]{foo:1b} 1").unwrap();
+        assert_eq!(rest, "1");
+        assert_eq!(item.id, ResourceLocation::new("diamond_sword").unwrap());
+        assert_eq!(
+            item.components,
+            vec![
+                ItemComponent::Set(ResourceLocation::new("damage").unwrap(), Nbt::Int(3)),
+                ItemComponent::Removed(ResourceLocation::new("food").unwrap()),
+            ]
+        );
+        assert_eq!(
+            item.to_string(),
+            "minecraft:diamond_sword[minecraft:damage=3,!minecraft:food]{foo:1b}"
+        );
+    }
+
+    #[test]
+    fn test_item_stack_errors() {
+        assert_eq!(
+            ItemStack::parse_from_command("stick[damage=1"),
+            Err("[damage=1")
+        );
+        assert_eq!(
+            ItemStack::parse_from_command("stick[damage=1,damage=2]"),
+            Err("damage=2]")
+        );
+        assert_eq!(ItemStack::parse_from_command("#swords"), Err("#swords"));
+    }
+
+    #[test]
+    fn test_item_predicate() {
+        let (_, predicate) = ItemPredicate::parse_from_command("#swords").unwrap();
+        assert_eq!(
+            predicate.item,
+            Some(TagOrId::Tag(ResourceLocation::new("swords").unwrap()))
+        );
+
+        let input = "*[minecraft:damage=0|!minecraft:unbreakable,minecraft:count~{min:2}]";
+        let (rest, predicate) = ItemPredicate::parse_from_command(input).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(predicate.item, None);
+        assert_eq!(predicate.tests.len(), 2);
+        assert_eq!(
+            predicate.tests[0].0[1],
+            ItemTest {
+                negated: true,
+                name: ResourceLocation::new("unbreakable").unwrap(),
+                kind: ItemTestKind::Exists,
+            }
+        );
+        assert_eq!(predicate.to_string(), input);
+    }
+}
@@ -1,6 +1,44 @@
 extern crate parse_macro;
 
-pub use parse_macro::parser;
+mod block;
+mod command_set;
+mod coordinates;
+mod display;
+mod item;
+mod nbt;
+mod nbt_path;
+mod range;
+mod recursion;
+mod resource_location;
+mod selector;
+mod text_component;
+mod time;
+mod uuid;
+pub mod vanilla;
+
+pub use block::{BlockPredicate, BlockProperties, BlockState};
+pub use command_set::CommandSet;
+#[doc(hidden)]
+pub use command_set::{concat_literals, literals_overlap};
+pub use coordinates::{
+    BlockPos, CoordinateValue, LocalCoordinate, Rotation, Vec2, Vec3, WorldCoordinate,
+};
+#[doc(hidden)]
+pub use display::{display_repeated, display_with, DisplayRepeated, DisplayWith};
+pub use item::{
+    ItemComponent, ItemPredicate, ItemStack, ItemTest, ItemTestAlternatives, ItemTestKind,
+};
+pub use nbt::{Nbt, NbtCompound};
+pub use nbt_path::{NbtPath, NbtPathSegment};
+pub use parse_macro::{parser, CommandParse};
+pub use range::{FloatRange, IntRange};
+#[doc(hidden)]
+pub use recursion::RecursionGuard;
+pub use resource_location::{ResourceLocation, ResourceLocationError, TagOrId};
+pub use selector::{SelectorArgument, SelectorKind, SelectorSort, SelectorValue, TargetSelector};
+pub use text_component::{Json, NbtSource, TextComponent, TextContent, TextStyle};
+pub use time::Ticks;
+pub use uuid::Uuid;
 
 pub trait CommandParse: std::fmt::Display + Sized {
     fn parse_from_command(value: &str) -> Result<(&str, Self), &str>;
@@ -49,15 +87,39 @@ pub fn parse_str(value: &str) -> (&str, &str) {
     (rest, value)
 }
 
+/// Strips the space which separates an argument from the next one.
+/// Fails with the rest of the input if the argument is followed by anything else.
+pub(crate) fn strip_separator(rest: &str) -> Result<&str, &str> {
+    if rest.is_empty() {
+        Ok(rest)
+    } else {
+        rest.strip_prefix(' ').ok_or(rest)
+    }
+}
+
+/// Splits off the longest prefix whose characters match `predicate` and returns a tuple of `(rest, prefix)`
+pub(crate) fn take_while(value: &str, predicate: impl Fn(char) -> bool) -> (&str, &str) {
+    let end = value.find(|c| !predicate(c)).unwrap_or(value.len());
+    let (prefix, rest) = value.split_at(end);
+    (rest, prefix)
+}
+
+/// Whether `c` may appear in an unquoted string
+pub(crate) fn is_unquoted_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.' || c == '+'
+}
+
 impl CommandParse for i32 {
     fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
         let end_idx = if let Some(value_neg) = value.strip_prefix('-') {
             value_neg
-                .find(|c: char| !c.is_digit(10))
+                .find(|c: char| !c.is_ascii_digit())
                 .unwrap_or(value_neg.len())
                 + 1
         } else {
-            value.find(|c: char| !c.is_digit(10)).unwrap_or(value.len())
+            value
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(value.len())
         };
 
         let (value_str, rest) = value.split_at(end_idx);
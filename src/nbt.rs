@@ -0,0 +1,407 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::{is_unquoted_char, strip_separator, take_while, CommandParse};
+
+/// The keys and values of a nbt compound, sorted by key
+pub type NbtCompound = BTreeMap<String, Nbt>;
+
+/// A nbt value, as written in its stringified form (SNBT)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Nbt {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    /// Must be finite, as infinity and NaN cannot be written as nbt
+    Float(f32),
+    /// Must be finite, as infinity and NaN cannot be written as nbt
+    Double(f64),
+    String(String),
+    List(Vec<Nbt>),
+    ByteArray(Vec<i8>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+    Compound(NbtCompound),
+}
+
+impl Nbt {
+    /// Parses a single nbt value and returns a tuple of `(rest, value)`,
+    /// where rest starts directly after the value
+    pub(crate) fn parse_value(value: &str) -> Result<(&str, Self), &str> {
+        if value.starts_with('{') {
+            let (rest, compound) = Self::parse_compound(value)?;
+            Ok((rest, Nbt::Compound(compound)))
+        } else if let Some(list) = value.strip_prefix('[') {
+            parse_list(list)
+        } else if value.starts_with('"') || value.starts_with('\'') {
+            let (rest, string) = parse_quoted_string(value)?;
+            Ok((rest, Nbt::String(string)))
+        } else {
+            let (rest, word) = take_while(value, is_unquoted_char);
+            if word.is_empty() {
+                return Err(value);
+            }
+            Ok((rest, parse_unquoted_value(word)))
+        }
+    }
+
+    /// Parses a compound like `{a:1,b:"foo"}` and returns a tuple of `(rest, compound)`
+    pub(crate) fn parse_compound(value: &str) -> Result<(&str, NbtCompound), &str> {
+        let mut compound = NbtCompound::new();
+        let mut rest = value.strip_prefix('{').ok_or(value)?.trim_start();
+        if let Some(rest) = rest.strip_prefix('}') {
+            return Ok((rest, compound));
+        }
+
+        loop {
+            let (after_key, key) = parse_key(rest)?;
+            let after_colon = after_key
+                .trim_start()
+                .strip_prefix(':')
+                .ok_or(after_key)?
+                .trim_start();
+            let (after_value, value) = Self::parse_value(after_colon)?;
+            compound.insert(key, value);
+
+            let after_value = after_value.trim_start();
+            if let Some(next) = after_value.strip_prefix(',') {
+                rest = next.trim_start();
+            } else if let Some(rest) = after_value.strip_prefix('}') {
+                return Ok((rest, compound));
+            } else {
+                return Err(after_value);
+            }
+        }
+    }
+}
+
+impl fmt::Display for Nbt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Nbt::Byte(value) => write!(f, "{}b", value),
+            Nbt::Short(value) => write!(f, "{}s", value),
+            Nbt::Int(value) => write!(f, "{}", value),
+            Nbt::Long(value) => write!(f, "{}L", value),
+            Nbt::Float(value) => write!(f, "{:?}f", value),
+            Nbt::Double(value) => write!(f, "{:?}d", value),
+            Nbt::String(value) => write_quoted(f, value),
+            Nbt::List(values) => write_sequence(f, "", values.iter()),
+            Nbt::ByteArray(values) => {
+                write_sequence(f, "B;", values.iter().map(|value| Nbt::Byte(*value)))
+            }
+            Nbt::IntArray(values) => {
+                write_sequence(f, "I;", values.iter().map(|value| Nbt::Int(*value)))
+            }
+            Nbt::LongArray(values) => {
+                write_sequence(f, "L;", values.iter().map(|value| Nbt::Long(*value)))
+            }
+            Nbt::Compound(compound) => write_compound(f, compound),
+        }
+    }
+}
+
+impl CommandParse for Nbt {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, nbt) = Self::parse_value(value)?;
+        let rest = strip_separator(rest)?;
+        Ok((rest, nbt))
+    }
+}
+
+fn write_sequence<T: fmt::Display>(
+    f: &mut fmt::Formatter<'_>,
+    prefix: &str,
+    values: impl Iterator<Item = T>,
+) -> fmt::Result {
+    write!(f, "[{}", prefix)?;
+    for (index, value) in values.enumerate() {
+        if index > 0 {
+            write!(f, ",")?;
+        }
+        write!(f, "{}", value)?;
+    }
+    write!(f, "]")
+}
+
+pub(crate) fn write_compound(f: &mut fmt::Formatter<'_>, compound: &NbtCompound) -> fmt::Result {
+    write!(f, "{{")?;
+    for (index, (key, value)) in compound.iter().enumerate() {
+        if index > 0 {
+            write!(f, ",")?;
+        }
+        if !key.is_empty() && key.chars().all(is_unquoted_char) {
+            write!(f, "{}", key)?;
+        } else {
+            write_quoted(f, key)?;
+        }
+        write!(f, ":{}", value)?;
+    }
+    write!(f, "}}")
+}
+
+/// Writes `value` as a quoted string, preferring double quotes unless the value contains any
+pub(crate) fn write_quoted(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    let quote = if value.contains('"') && !value.contains('\'') {
+        '\''
+    } else {
+        '"'
+    };
+    write!(f, "{}", quote)?;
+    for c in value.chars() {
+        if c == quote || c == '\\' {
+            write!(f, "\\")?;
+        }
+        write!(f, "{}", c)?;
+    }
+    write!(f, "{}", quote)
+}
+
+/// Parses a string enclosed in single or double quotes and returns a tuple of `(rest, string)`
+pub(crate) fn parse_quoted_string(value: &str) -> Result<(&str, String), &str> {
+    let mut chars = value.char_indices();
+    let quote = match chars.next() {
+        Some((_, quote @ '"')) | Some((_, quote @ '\'')) => quote,
+        _ => return Err(value),
+    };
+
+    let mut string = String::new();
+    let mut escaped = false;
+    for (index, c) in chars {
+        if escaped {
+            if c != quote && c != '\\' {
+                return Err(&value[index..]);
+            }
+            string.push(c);
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            return Ok((&value[index + 1..], string));
+        } else {
+            string.push(c);
+        }
+    }
+    Err(value)
+}
+
+/// Parses a compound key, which is either quoted or unquoted
+fn parse_key(value: &str) -> Result<(&str, String), &str> {
+    if value.starts_with('"') || value.starts_with('\'') {
+        parse_quoted_string(value)
+    } else {
+        let (rest, key) = take_while(value, is_unquoted_char);
+        if key.is_empty() {
+            return Err(value);
+        }
+        Ok((rest, key.to_string()))
+    }
+}
+
+/// Parses the contents of a list or typed array after its opening bracket
+fn parse_list(value: &str) -> Result<(&str, Nbt), &str> {
+    let array_kind = ["B;", "I;", "L;"]
+        .iter()
+        .find(|prefix| value.starts_with(*prefix));
+    let elements = match array_kind {
+        Some(prefix) => &value[prefix.len()..],
+        None => value,
+    };
+
+    let mut values = Vec::new();
+    let mut rest = elements.trim_start();
+    if let Some(rest) = rest.strip_prefix(']') {
+        return Ok((rest, build_list(array_kind, values, elements)?));
+    }
+
+    loop {
+        let (after_value, value) = Nbt::parse_value(rest)?;
+        values.push(value);
+
+        let after_value = after_value.trim_start();
+        if let Some(next) = after_value.strip_prefix(',') {
+            rest = next.trim_start();
+        } else if let Some(after_list) = after_value.strip_prefix(']') {
+            let list = build_list(array_kind, values, elements)?;
+            return Ok((after_list, list));
+        } else {
+            return Err(after_value);
+        }
+    }
+}
+
+/// Converts the parsed values to a typed array if requested, failing with `error` for elements of another type
+fn build_list<'a>(
+    array_kind: Option<&&str>,
+    values: Vec<Nbt>,
+    error: &'a str,
+) -> Result<Nbt, &'a str> {
+    fn collect<T>(values: &[Nbt], element: fn(&Nbt) -> Option<T>) -> Option<Vec<T>> {
+        values.iter().map(element).collect()
+    }
+
+    let list = match array_kind.copied() {
+        Some("B;") => collect(&values, |value| match value {
+            Nbt::Byte(value) => Some(*value),
+            _ => None,
+        })
+        .map(Nbt::ByteArray),
+        Some("I;") => collect(&values, |value| match value {
+            Nbt::Int(value) => Some(*value),
+            _ => None,
+        })
+        .map(Nbt::IntArray),
+        Some(_) => collect(&values, |value| match value {
+            Nbt::Long(value) => Some(*value),
+            _ => None,
+        })
+        .map(Nbt::LongArray),
+        None => Some(Nbt::List(values)),
+    };
+    list.ok_or(error)
+}
+
+/// Interprets an unquoted word as a number or boolean, falling back to a string like vanilla does
+fn parse_unquoted_value(word: &str) -> Nbt {
+    let lower = word.to_ascii_lowercase();
+    let (number, suffix) = match lower.char_indices().last() {
+        Some((index, c)) if c.is_ascii_alphabetic() => (&lower[..index], Some(c)),
+        _ => (lower.as_str(), None),
+    };
+
+    let value = match suffix {
+        Some('b') if is_integer(number) => number.parse().ok().map(Nbt::Byte),
+        Some('s') if is_integer(number) => number.parse().ok().map(Nbt::Short),
+        Some('l') if is_integer(number) => number.parse().ok().map(Nbt::Long),
+        // Numbers which are too large for their type would not be written back as numbers
+        Some('f') if is_decimal(number) => number
+            .parse()
+            .ok()
+            .filter(|value: &f32| value.is_finite())
+            .map(Nbt::Float),
+        Some('d') if is_decimal(number) => parse_finite(number).map(Nbt::Double),
+        None if is_integer(number) => number.parse().ok().map(Nbt::Int),
+        None if is_decimal(number) && number.contains('.') => parse_finite(number).map(Nbt::Double),
+        _ => match lower.as_str() {
+            "true" => Some(Nbt::Byte(1)),
+            "false" => Some(Nbt::Byte(0)),
+            _ => None,
+        },
+    };
+    value.unwrap_or_else(|| Nbt::String(word.to_string()))
+}
+
+fn parse_finite(value: &str) -> Option<f64> {
+    value.parse().ok().filter(|value: &f64| value.is_finite())
+}
+
+fn strip_sign(value: &str) -> &str {
+    value
+        .strip_prefix(|c| c == '-' || c == '+')
+        .unwrap_or(value)
+}
+
+/// Matches `[-+]?(0|[1-9][0-9]*)`
+fn is_integer(value: &str) -> bool {
+    let digits = strip_sign(value);
+    !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit())
+        && (digits == "0" || !digits.starts_with('0'))
+}
+
+/// Matches `[-+]?([0-9]+[.]?|[0-9]*[.][0-9]+)(e[-+]?[0-9]+)?`
+fn is_decimal(value: &str) -> bool {
+    let (mantissa, exponent) = match value.split_once('e') {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (value, None),
+    };
+    let mantissa = strip_sign(mantissa);
+    let (integral, fractional) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let is_digits = |value: &str| value.chars().all(|c| c.is_ascii_digit());
+
+    let valid_mantissa = is_digits(integral)
+        && is_digits(fractional)
+        && !(integral.is_empty() && fractional.is_empty());
+    let valid_exponent = exponent.map_or(true, |exponent| {
+        let exponent = strip_sign(exponent);
+        !exponent.is_empty() && is_digits(exponent)
+    });
+    valid_mantissa && valid_exponent
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::Nbt;
+    use crate::CommandParse;
+
+    fn parse(value: &str) -> Nbt {
+        let (rest, nbt) = Nbt::parse_from_command(value).unwrap();
+        assert_eq!(rest, "");
+        nbt
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(parse("1b"), Nbt::Byte(1));
+        assert_eq!(parse("-3S"), Nbt::Short(-3));
+        assert_eq!(parse("42"), Nbt::Int(42));
+        assert_eq!(parse("42L"), Nbt::Long(42));
+        assert_eq!(parse("1.5f"), Nbt::Float(1.5));
+        assert_eq!(parse("1e39f"), Nbt::String("1e39f".to_string()));
+        assert_eq!(parse("-1e999"), Nbt::String("-1e999".to_string()));
+        assert_eq!(parse("1e308d"), Nbt::Double(1e308));
+        assert_eq!(parse("1d"), Nbt::Double(1.0));
+        assert_eq!(parse(".5"), Nbt::Double(0.5));
+        assert_eq!(parse("1e3d"), Nbt::Double(1000.0));
+        assert_eq!(parse("true"), Nbt::Byte(1));
+        assert_eq!(parse("300b"), Nbt::String("300b".to_string()));
+        assert_eq!(parse("012"), Nbt::String("012".to_string()));
+    }
+
+    #[test]
+    fn test_compound() {
+        let nbt = parse("{ b : [I; 1, 2], a:'say \"hi\"', \"c d\":[{}, {x:1.0}] }");
+        let mut inner = BTreeMap::new();
+        inner.insert("x".to_string(), Nbt::Double(1.0));
+        let mut expected = BTreeMap::new();
+        expected.insert("a".to_string(), Nbt::String("say \"hi\"".to_string()));
+        expected.insert("b".to_string(), Nbt::IntArray(vec![1, 2]));
+        expected.insert(
+            "c d".to_string(),
+            Nbt::List(vec![Nbt::Compound(BTreeMap::new()), Nbt::Compound(inner)]),
+        );
+        assert_eq!(nbt, Nbt::Compound(expected));
+        assert_eq!(
+            nbt.to_string(),
+            "{a:'say \"hi\"',b:[I;1,2],\"c d\":[{},{x:1.0d}]}"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let input = "{a:[B;1b,-2b],b:[L;3L],c:[],d:\"\\\\\",e:2.5f,f:[1s,2s]}";
+        assert_eq!(parse(input).to_string(), input);
+
+        for input in &["3.4028235e38f", "1e39f", "-1e999d", "1.0e-45f"] {
+            let nbt = parse(input);
+            assert_eq!(parse(&nbt.to_string()), nbt);
+        }
+    }
+
+    #[test]
+    fn test_consumes_one_value() {
+        assert_eq!(
+            Nbt::parse_from_command("{a:1} {b:2}"),
+            Ok(("{b:2}", parse("{a:1}")))
+        );
+        assert_eq!(Nbt::parse_from_command("{a:1"), Err(""));
+        assert_eq!(Nbt::parse_from_command("{a:1}}"), Err("}"));
+        assert_eq!(Nbt::parse_from_command("[B;1b,300]"), Err("1b,300]"));
+        assert_eq!(Nbt::parse_from_command("[I;1b]"), Err("1b]"));
+        assert_eq!(Nbt::parse_from_command("[B;1L]"), Err("1L]"));
+        assert_eq!(Nbt::parse_from_command("[L;1]"), Err("1]"));
+        assert_eq!(parse("[I;1,-2]"), Nbt::IntArray(vec![1, -2]));
+        assert_eq!(Nbt::parse_from_command("'foo\\n'"), Err("n'"));
+    }
+}
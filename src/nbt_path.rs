@@ -0,0 +1,220 @@
+use std::fmt;
+
+use crate::{
+    nbt::{parse_quoted_string, write_compound, write_quoted},
+    strip_separator, take_while, CommandParse, Nbt, NbtCompound,
+};
+
+/// A single segment of a [`NbtPath`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum NbtPathSegment {
+    /// A compound which the root value has to match, like `{Invisible:1b}`.
+    /// Only allowed as the first segment.
+    MatchRootObject(NbtCompound),
+    /// A named key, like `Inventory` or `"quoted key"`
+    CompoundChild(String),
+    /// A named key whose value has to match a compound, like `Item{id:"minecraft:stone"}`
+    MatchObject(String, NbtCompound),
+    /// All elements of a list, `[]`
+    AllElements,
+    /// The element at an index, like `[0]` or `[-1]`
+    IndexedElement(i32),
+    /// All elements of a list which match a compound, like `[{Slot:0b}]`
+    MatchElement(NbtCompound),
+}
+
+impl NbtPathSegment {
+    fn is_named(&self) -> bool {
+        matches!(
+            self,
+            NbtPathSegment::CompoundChild(_) | NbtPathSegment::MatchObject(_, _)
+        )
+    }
+}
+
+impl fmt::Display for NbtPathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NbtPathSegment::MatchRootObject(compound) => write_compound(f, compound),
+            NbtPathSegment::CompoundChild(name) => write_name(f, name),
+            NbtPathSegment::MatchObject(name, compound) => {
+                write_name(f, name)?;
+                write_compound(f, compound)
+            }
+            NbtPathSegment::AllElements => write!(f, "[]"),
+            NbtPathSegment::IndexedElement(index) => write!(f, "[{}]", index),
+            NbtPathSegment::MatchElement(compound) => {
+                write!(f, "[")?;
+                write_compound(f, compound)?;
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+/// A path into a nbt value, like `Inventory[{Slot:0b}].tag.display.Name`
+#[derive(Debug, Clone, PartialEq)]
+pub struct NbtPath {
+    pub segments: Vec<NbtPathSegment>,
+}
+
+impl fmt::Display for NbtPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, segment) in self.segments.iter().enumerate() {
+            if index > 0 && segment.is_named() {
+                write!(f, ".")?;
+            }
+            write!(f, "{}", segment)?;
+        }
+        Ok(())
+    }
+}
+
+impl CommandParse for NbtPath {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        let mut segments = Vec::new();
+        let mut rest = value;
+        while !rest.is_empty() && !rest.starts_with(' ') {
+            let (after_segment, segment) = parse_segment(rest, segments.is_empty())?;
+            segments.push(segment);
+
+            rest = match after_segment.chars().next() {
+                None | Some(' ') | Some('[') | Some('{') => after_segment,
+                Some('.') => {
+                    let next = &after_segment[1..];
+                    if next.is_empty() || next.starts_with(' ') {
+                        return Err(next);
+                    }
+                    next
+                }
+                Some(_) => return Err(after_segment),
+            };
+        }
+
+        if segments.is_empty() {
+            return Err(value);
+        }
+        let rest = strip_separator(rest)?;
+        Ok((rest, NbtPath { segments }))
+    }
+}
+
+/// Whether `c` may appear in an unquoted key of a nbt path
+fn is_unquoted_name_char(c: char) -> bool {
+    !matches!(c, ' ' | '"' | '\'' | '[' | ']' | '.' | '{' | '}')
+}
+
+fn write_name(f: &mut fmt::Formatter<'_>, name: &str) -> fmt::Result {
+    if !name.is_empty() && name.chars().all(is_unquoted_name_char) {
+        write!(f, "{}", name)
+    } else {
+        write_quoted(f, name)
+    }
+}
+
+/// Parses a single segment and returns a tuple of `(rest, segment)`
+fn parse_segment(value: &str, is_first: bool) -> Result<(&str, NbtPathSegment), &str> {
+    if value.starts_with('{') {
+        if !is_first {
+            return Err(value);
+        }
+        let (rest, compound) = Nbt::parse_compound(value)?;
+        return Ok((rest, NbtPathSegment::MatchRootObject(compound)));
+    }
+
+    if let Some(index) = value.strip_prefix('[') {
+        let (rest, segment) = if index.starts_with('{') {
+            let (rest, compound) = Nbt::parse_compound(index)?;
+            (rest, NbtPathSegment::MatchElement(compound))
+        } else if index.starts_with(']') {
+            (index, NbtPathSegment::AllElements)
+        } else {
+            let (rest, number) = take_while(index, |c| c.is_ascii_digit() || c == '-');
+            let number = number.parse().map_err(|_| index)?;
+            (rest, NbtPathSegment::IndexedElement(number))
+        };
+        let rest = rest.strip_prefix(']').ok_or(rest)?;
+        return Ok((rest, segment));
+    }
+
+    let (rest, name) = if value.starts_with('"') || value.starts_with('\'') {
+        parse_quoted_string(value)?
+    } else {
+        let (rest, name) = take_while(value, is_unquoted_name_char);
+        if name.is_empty() {
+            return Err(value);
+        }
+        (rest, name.to_string())
+    };
+
+    if rest.starts_with('{') {
+        let (rest, compound) = Nbt::parse_compound(rest)?;
+        Ok((rest, NbtPathSegment::MatchObject(name, compound)))
+    } else {
+        Ok((rest, NbtPathSegment::CompoundChild(name)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{NbtPath, NbtPathSegment};
+    use crate::{CommandParse, Nbt, NbtCompound};
+
+    #[test]
+    fn test_nbt_path() {
+        let input = "Inventory[{Slot:0b}].tag.display.Name";
+        let (rest, path) = NbtPath::parse_from_command(input).unwrap();
+        assert_eq!(rest, "");
+
+        let mut filter = NbtCompound::new();
+        filter.insert("Slot".to_string(), Nbt::Byte(0));
+        assert_eq!(
+            path.segments,
+            vec![
+                NbtPathSegment::CompoundChild("Inventory".to_string()),
+                NbtPathSegment::MatchElement(filter),
+                NbtPathSegment::CompoundChild("tag".to_string()),
+                NbtPathSegment::CompoundChild("display".to_string()),
+                NbtPathSegment::CompoundChild("Name".to_string()),
+            ]
+        );
+        assert_eq!(path.to_string(), input);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for input in &[
+            "{Invisible:1b}.Pos[-1]",
+            "\"a key\".b[][0]",
+            "Items[].tag{Damage:0}.x",
+            "a.\"b.c\"",
+        ] {
+            let (rest, path) = NbtPath::parse_from_command(input).unwrap();
+            assert_eq!(rest, "");
+            assert_eq!(&path.to_string(), input);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(NbtPath::parse_from_command(" a"), Err(" a"));
+        assert_eq!(NbtPath::parse_from_command("a.{b:1}"), Err("{b:1}"));
+        assert_eq!(NbtPath::parse_from_command("a[x]"), Err("x]"));
+        assert_eq!(NbtPath::parse_from_command("a[0"), Err(""));
+        assert_eq!(NbtPath::parse_from_command("a..b"), Err(".b"));
+        assert_eq!(NbtPath::parse_from_command("a."), Err(""));
+        assert_eq!(NbtPath::parse_from_command("a. b"), Err(" b"));
+        assert_eq!(
+            NbtPath::parse_from_command("a.b foo"),
+            Ok((
+                "foo",
+                NbtPath {
+                    segments: vec![
+                        NbtPathSegment::CompoundChild("a".to_string()),
+                        NbtPathSegment::CompoundChild("b".to_string()),
+                    ]
+                }
+            ))
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use std::{fmt, str::FromStr};
+
+use crate::{strip_separator, CommandParse};
+
+/// Splits off a number at the start of `value`, stopping before a `..` and returns a tuple of `(rest, number)`
+fn read_number(value: &str) -> (&str, &str) {
+    let mut end = 0;
+    for (index, c) in value.char_indices() {
+        let is_number_char = match c {
+            '0'..='9' | '-' => true,
+            '.' => !value[index + 1..].starts_with('.'),
+            _ => false,
+        };
+        if !is_number_char {
+            break;
+        }
+        end = index + 1;
+    }
+    let (number, rest) = value.split_at(end);
+    (rest, number)
+}
+
+/// Parses bounds like `1..5`, `..3`, `2..` or `4` and returns a tuple of `(rest, min, max)`
+fn parse_bounds<T>(value: &str) -> Result<(&str, Option<T>, Option<T>), &str>
+where
+    T: FromStr + PartialOrd + Copy,
+{
+    let parse = |number: &str| -> Result<Option<T>, ()> {
+        if number.is_empty() {
+            Ok(None)
+        } else {
+            number.parse().map(Some).map_err(|_| ())
+        }
+    };
+
+    let (after_min, min) = read_number(value);
+    let min = parse(min).map_err(|_| value)?;
+    let (rest, min, max) = match after_min.strip_prefix("..") {
+        Some(after_dots) => {
+            let (rest, max) = read_number(after_dots);
+            let max = parse(max).map_err(|_| after_dots)?;
+            (rest, min, max)
+        }
+        None => (after_min, min, min),
+    };
+
+    match (min, max) {
+        (None, None) => Err(value),
+        (Some(min), Some(max)) if min > max => Err(value),
+        _ => Ok((rest, min, max)),
+    }
+}
+
+fn write_bounds<T>(f: &mut fmt::Formatter<'_>, min: Option<T>, max: Option<T>) -> fmt::Result
+where
+    T: fmt::Display + PartialEq,
+{
+    match (min, max) {
+        (Some(min), Some(max)) if min == max => write!(f, "{}", min),
+        (min, max) => {
+            if let Some(min) = min {
+                write!(f, "{}", min)?;
+            }
+            write!(f, "..")?;
+            if let Some(max) = max {
+                write!(f, "{}", max)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// An inclusive range of integers like `1..5`, `..3`, `2..` or `4`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IntRange {
+    pub min: Option<i32>,
+    pub max: Option<i32>,
+}
+
+impl IntRange {
+    pub fn contains(&self, value: i32) -> bool {
+        self.min.map_or(true, |min| min <= value) && self.max.map_or(true, |max| value <= max)
+    }
+
+    /// Parses a range at the start of `value` and returns a tuple of `(rest, range)`
+    pub(crate) fn parse_prefix(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, min, max) = parse_bounds(value)?;
+        Ok((rest, IntRange { min, max }))
+    }
+}
+
+impl fmt::Display for IntRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_bounds(f, self.min, self.max)
+    }
+}
+
+impl CommandParse for IntRange {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, range) = Self::parse_prefix(value)?;
+        let rest = strip_separator(rest)?;
+        Ok((rest, range))
+    }
+}
+
+/// An inclusive range of decimal numbers like `0.5..5`, `..3.2`, `2..` or `4`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FloatRange {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl FloatRange {
+    pub fn contains(&self, value: f64) -> bool {
+        self.min.map_or(true, |min| min <= value) && self.max.map_or(true, |max| value <= max)
+    }
+
+    /// Parses a range at the start of `value` and returns a tuple of `(rest, range)`
+    pub(crate) fn parse_prefix(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, min, max) = parse_bounds(value)?;
+        Ok((rest, FloatRange { min, max }))
+    }
+}
+
+impl fmt::Display for FloatRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_bounds(f, self.min, self.max)
+    }
+}
+
+impl CommandParse for FloatRange {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, range) = Self::parse_prefix(value)?;
+        let rest = strip_separator(rest)?;
+        Ok((rest, range))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{FloatRange, IntRange};
+    use crate::CommandParse;
+
+    #[test]
+    fn test_int_range() {
+        assert_eq!(
+            IntRange::parse_from_command("1..5 foo"),
+            Ok((
+                "foo",
+                IntRange {
+                    min: Some(1),
+                    max: Some(5)
+                }
+            ))
+        );
+        assert_eq!(
+            IntRange::parse_from_command("-3.."),
+            Ok((
+                "",
+                IntRange {
+                    min: Some(-3),
+                    max: None
+                }
+            ))
+        );
+        let (_, range) = IntRange::parse_from_command("..3").unwrap();
+        assert!(range.contains(-100));
+        assert!(range.contains(3));
+        assert!(!range.contains(4));
+    }
+
+    #[test]
+    fn test_float_range() {
+        let (rest, range) = FloatRange::parse_from_command(".5..1.5").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            range,
+            FloatRange {
+                min: Some(0.5),
+                max: Some(1.5)
+            }
+        );
+        assert!(range.contains(1.0));
+        assert!(!range.contains(2.0));
+    }
+
+    #[test]
+    fn test_display() {
+        for (input, output) in &[
+            ("1..5", "1..5"),
+            ("3..3", "3"),
+            ("..3", "..3"),
+            ("2..", "2.."),
+        ] {
+            let (_, range) = IntRange::parse_from_command(input).unwrap();
+            assert_eq!(&range.to_string(), output);
+        }
+        let (_, range) = FloatRange::parse_from_command("1.0..2.50").unwrap();
+        assert_eq!(range.to_string(), "1..2.5");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(IntRange::parse_from_command(".."), Err(".."));
+        assert_eq!(IntRange::parse_from_command("5..1"), Err("5..1"));
+        assert_eq!(IntRange::parse_from_command("1.5"), Err("1.5"));
+        assert_eq!(IntRange::parse_from_command("1..x"), Err("x"));
+        assert_eq!(FloatRange::parse_from_command("1..2..3"), Err("..3"));
+    }
+}
@@ -0,0 +1,44 @@
+use std::cell::Cell;
+
+thread_local! {
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Counts how many redirects like the command after `execute ... run` are nested on the current thread,
+/// so that commands like `execute run execute run ...` cannot overflow the stack
+#[doc(hidden)]
+pub struct RecursionGuard(());
+
+impl RecursionGuard {
+    /// Enters another level of parsing, or returns `None` if `limit` levels are already entered
+    pub fn enter(limit: usize) -> Option<Self> {
+        DEPTH.with(|depth| {
+            if depth.get() >= limit {
+                return None;
+            }
+            depth.set(depth.get() + 1);
+            Some(RecursionGuard(()))
+        })
+    }
+}
+
+impl Drop for RecursionGuard {
+    fn drop(&mut self) {
+        DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RecursionGuard;
+
+    #[test]
+    fn test_recursion_guard() {
+        let first = RecursionGuard::enter(2).unwrap();
+        let second = RecursionGuard::enter(2).unwrap();
+        assert!(RecursionGuard::enter(2).is_none());
+        drop(second);
+        assert!(RecursionGuard::enter(2).is_some());
+        drop(first);
+    }
+}
@@ -0,0 +1,267 @@
+use std::{error::Error, fmt, str::FromStr};
+
+use crate::{strip_separator, take_while, CommandParse};
+
+const DEFAULT_NAMESPACE: &str = "minecraft";
+
+/// The reason why a string is not a valid [`ResourceLocation`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceLocationError {
+    Empty,
+    /// A character which is not allowed at this position, given as a byte offset
+    InvalidCharacter {
+        position: usize,
+        character: char,
+    },
+}
+
+impl fmt::Display for ResourceLocationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResourceLocationError::Empty => write!(f, "Expected a resource location"),
+            ResourceLocationError::InvalidCharacter {
+                position,
+                character,
+            } => write!(
+                f,
+                "Illegal character {:?} at position {} in resource location",
+                character, position
+            ),
+        }
+    }
+}
+
+impl Error for ResourceLocationError {}
+
+/// A namespaced id like `minecraft:stone`. The namespace defaults to `minecraft` if omitted.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ResourceLocation {
+    pub namespace: String,
+    pub path: String,
+}
+
+impl ResourceLocation {
+    /// Validates `value` and splits it into namespace and path
+    pub fn new(value: &str) -> Result<Self, ResourceLocationError> {
+        if value.is_empty() {
+            return Err(ResourceLocationError::Empty);
+        }
+
+        let (namespace, path, path_offset) = match value.split_once(':') {
+            Some((namespace, path)) => (namespace, path, namespace.len() + 1),
+            None => (DEFAULT_NAMESPACE, value, 0),
+        };
+        if path_offset > 0 {
+            validate(namespace, 0, is_namespace_char)?;
+        }
+        validate(path, path_offset, is_path_char)?;
+
+        // Like vanilla, `:stone` is in the default namespace
+        let namespace = if namespace.is_empty() {
+            DEFAULT_NAMESPACE
+        } else {
+            namespace
+        };
+        Ok(ResourceLocation {
+            namespace: namespace.to_string(),
+            path: path.to_string(),
+        })
+    }
+
+    /// Parses a resource location at the start of `value` and returns a tuple of `(rest, location)`
+    pub(crate) fn parse_prefix(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, location) = take_while(value, is_resource_location_char);
+        match ResourceLocation::new(location) {
+            Ok(location) => Ok((rest, location)),
+            Err(ResourceLocationError::Empty) => Err(value),
+            Err(ResourceLocationError::InvalidCharacter { position, .. }) => {
+                Err(&value[position..])
+            }
+        }
+    }
+}
+
+fn validate(
+    value: &str,
+    offset: usize,
+    is_valid: impl Fn(char) -> bool,
+) -> Result<(), ResourceLocationError> {
+    match value.char_indices().find(|(_, c)| !is_valid(*c)) {
+        Some((position, character)) => Err(ResourceLocationError::InvalidCharacter {
+            position: position + offset,
+            character,
+        }),
+        None => Ok(()),
+    }
+}
+
+fn is_namespace_char(c: char) -> bool {
+    c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-' || c == '.'
+}
+
+fn is_path_char(c: char) -> bool {
+    is_namespace_char(c) || c == '/'
+}
+
+/// Whether `c` may appear anywhere in a resource location
+fn is_resource_location_char(c: char) -> bool {
+    is_path_char(c) || c == ':'
+}
+
+impl fmt::Display for ResourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.namespace, self.path)
+    }
+}
+
+impl FromStr for ResourceLocation {
+    type Err = ResourceLocationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ResourceLocation::new(s)
+    }
+}
+
+impl CommandParse for ResourceLocation {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, location) = Self::parse_prefix(value)?;
+        let rest = strip_separator(rest)?;
+        Ok((rest, location))
+    }
+}
+
+/// Either a tag like `#minecraft:logs` or a single id like `minecraft:oak_log`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TagOrId {
+    Tag(ResourceLocation),
+    Id(ResourceLocation),
+}
+
+impl TagOrId {
+    /// Validates `value`, which refers to a tag if it starts with `#`
+    pub fn new(value: &str) -> Result<Self, ResourceLocationError> {
+        match value.strip_prefix('#') {
+            Some(tag) => {
+                ResourceLocation::new(tag)
+                    .map(TagOrId::Tag)
+                    .map_err(|error| match error {
+                        ResourceLocationError::InvalidCharacter {
+                            position,
+                            character,
+                        } => ResourceLocationError::InvalidCharacter {
+                            position: position + 1,
+                            character,
+                        },
+                        ResourceLocationError::Empty => error,
+                    })
+            }
+            None => ResourceLocation::new(value).map(TagOrId::Id),
+        }
+    }
+
+    pub fn location(&self) -> &ResourceLocation {
+        match self {
+            TagOrId::Tag(location) | TagOrId::Id(location) => location,
+        }
+    }
+
+    /// Parses a tag or id at the start of `value` and returns a tuple of `(rest, tag_or_id)`
+    pub(crate) fn parse_prefix(value: &str) -> Result<(&str, Self), &str> {
+        match value.strip_prefix('#') {
+            Some(tag) => {
+                let (rest, location) = ResourceLocation::parse_prefix(tag)?;
+                Ok((rest, TagOrId::Tag(location)))
+            }
+            None => {
+                let (rest, location) = ResourceLocation::parse_prefix(value)?;
+                Ok((rest, TagOrId::Id(location)))
+            }
+        }
+    }
+}
+
+impl fmt::Display for TagOrId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TagOrId::Tag(location) => write!(f, "#{}", location),
+            TagOrId::Id(location) => write!(f, "{}", location),
+        }
+    }
+}
+
+impl FromStr for TagOrId {
+    type Err = ResourceLocationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TagOrId::new(s)
+    }
+}
+
+impl CommandParse for TagOrId {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, tag_or_id) = Self::parse_prefix(value)?;
+        let rest = strip_separator(rest)?;
+        Ok((rest, tag_or_id))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ResourceLocation, ResourceLocationError, TagOrId};
+    use crate::CommandParse;
+
+    #[test]
+    fn test_resource_location() {
+        let stone = ResourceLocation::new("stone").unwrap();
+        assert_eq!(stone, ResourceLocation::new("minecraft:stone").unwrap());
+        assert_eq!(stone.to_string(), "minecraft:stone");
+        assert_eq!(stone, ResourceLocation::new(":stone").unwrap());
+        assert_eq!(
+            ResourceLocation::new("my_pack:functions/tick").map(|location| location.namespace),
+            Ok("my_pack".to_string())
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(ResourceLocation::new(""), Err(ResourceLocationError::Empty));
+        assert_eq!(
+            ResourceLocation::new("minecraft:Stone"),
+            Err(ResourceLocationError::InvalidCharacter {
+                position: 10,
+                character: 'S'
+            })
+        );
+        assert_eq!(
+            ResourceLocation::new("my/pack:stone"),
+            Err(ResourceLocationError::InvalidCharacter {
+                position: 2,
+                character: '/'
+            })
+        );
+        assert_eq!(
+            TagOrId::new("#a:b:c"),
+            Err(ResourceLocationError::InvalidCharacter {
+                position: 4,
+                character: ':'
+            })
+        );
+        assert_eq!(
+            ResourceLocation::parse_from_command("minecraft:Stone"),
+            Err("Stone")
+        );
+        assert_eq!(ResourceLocation::parse_from_command("a:b:c"), Err(":c"));
+    }
+
+    #[test]
+    fn test_tag_or_id() {
+        let (rest, tag) = TagOrId::parse_from_command("#minecraft:logs foo").unwrap();
+        assert_eq!(rest, "foo");
+        assert_eq!(tag, TagOrId::Tag(ResourceLocation::new("logs").unwrap()));
+        assert_eq!(tag.to_string(), "#minecraft:logs");
+        assert_eq!(
+            TagOrId::parse_from_command("oak_log"),
+            Ok(("", TagOrId::Id(ResourceLocation::new("oak_log").unwrap())))
+        );
+    }
+}
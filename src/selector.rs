@@ -0,0 +1,564 @@
+use std::fmt;
+
+use crate::{
+    is_unquoted_char, strip_separator, take_while, CommandParse, FloatRange, IntRange, Nbt, Uuid,
+};
+
+/// All selector arguments known to vanilla
+const ARGUMENT_NAMES: &[&str] = &[
+    "x",
+    "y",
+    "z",
+    "distance",
+    "dx",
+    "dy",
+    "dz",
+    "x_rotation",
+    "y_rotation",
+    "scores",
+    "tag",
+    "team",
+    "name",
+    "type",
+    "nbt",
+    "predicate",
+    "level",
+    "gamemode",
+    "advancements",
+    "limit",
+    "sort",
+];
+
+/// Selector arguments which may be inverted with a `!`
+const NEGATABLE_ARGUMENT_NAMES: &[&str] = &[
+    "tag",
+    "team",
+    "name",
+    "type",
+    "nbt",
+    "predicate",
+    "gamemode",
+];
+
+/// The kind of a target selector, for example `@a`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SelectorKind {
+    /// `@a`
+    AllPlayers,
+    /// `@e`
+    AllEntities,
+    /// `@p`
+    NearestPlayer,
+    /// `@r`
+    RandomPlayer,
+    /// `@s`
+    Executor,
+    /// `@n`
+    NearestEntity,
+}
+
+impl SelectorKind {
+    fn from_char(c: char) -> Option<Self> {
+        let kind = match c {
+            'a' => SelectorKind::AllPlayers,
+            'e' => SelectorKind::AllEntities,
+            'p' => SelectorKind::NearestPlayer,
+            'r' => SelectorKind::RandomPlayer,
+            's' => SelectorKind::Executor,
+            'n' => SelectorKind::NearestEntity,
+            _ => return None,
+        };
+        Some(kind)
+    }
+}
+
+impl fmt::Display for SelectorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = match self {
+            SelectorKind::AllPlayers => 'a',
+            SelectorKind::AllEntities => 'e',
+            SelectorKind::NearestPlayer => 'p',
+            SelectorKind::RandomPlayer => 'r',
+            SelectorKind::Executor => 's',
+            SelectorKind::NearestEntity => 'n',
+        };
+        write!(f, "@{}", c)
+    }
+}
+
+/// The value of a selector argument
+#[derive(Debug, Clone, PartialEq)]
+pub enum SelectorValue {
+    /// A value as written, for example `zombie`, `..5` or `"Some name"`
+    Plain(String),
+    /// A nested map, as used by `scores` and `advancements`
+    Map(Vec<(String, SelectorValue)>),
+    /// The compound of a `nbt` argument
+    Nbt(Nbt),
+}
+
+impl fmt::Display for SelectorValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectorValue::Plain(value) => write!(f, "{}", value),
+            SelectorValue::Nbt(value) => write!(f, "{}", value),
+            SelectorValue::Map(entries) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}={}", key, value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// A single `name=value` argument of a target selector
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectorArgument {
+    pub name: String,
+    pub negated: bool,
+    pub value: SelectorValue,
+}
+
+impl fmt::Display for SelectorArgument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let negation = if self.negated { "!" } else { "" };
+        write!(f, "{}={}{}", self.name, negation, self.value)
+    }
+}
+
+/// The sort order of a selector, given by its `sort` argument
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SelectorSort {
+    Nearest,
+    Furthest,
+    Random,
+    Arbitrary,
+}
+
+impl SelectorSort {
+    fn from_str(value: &str) -> Option<Self> {
+        let sort = match value {
+            "nearest" => SelectorSort::Nearest,
+            "furthest" => SelectorSort::Furthest,
+            "random" => SelectorSort::Random,
+            "arbitrary" => SelectorSort::Arbitrary,
+            _ => return None,
+        };
+        Some(sort)
+    }
+}
+
+/// An entity argument, which is either a selector like `@e[type=zombie]`, a player name or a uuid
+#[derive(Debug, Clone, PartialEq)]
+pub enum TargetSelector {
+    Selector {
+        kind: SelectorKind,
+        arguments: Vec<SelectorArgument>,
+    },
+    Player(String),
+    Uuid(Uuid),
+}
+
+impl TargetSelector {
+    /// Returns all arguments of this selector with the given name
+    pub fn arguments<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a SelectorArgument> {
+        let arguments = match self {
+            TargetSelector::Selector { arguments, .. } => arguments.as_slice(),
+            _ => &[],
+        };
+        arguments
+            .iter()
+            .filter(move |argument| argument.name == name)
+    }
+
+    /// Returns the value of the `limit` argument, if specified
+    pub fn limit(&self) -> Option<i32> {
+        self.arguments("limit")
+            .find_map(|argument| plain_value(argument)?.parse().ok())
+    }
+
+    /// Returns the value of the `sort` argument, if specified
+    pub fn sort(&self) -> Option<SelectorSort> {
+        self.arguments("sort")
+            .find_map(|argument| SelectorSort::from_str(plain_value(argument)?))
+    }
+
+    /// Returns the value of the `distance` argument, if specified
+    pub fn distance(&self) -> Option<FloatRange> {
+        self.arguments("distance")
+            .find_map(|argument| parse_range(plain_value(argument)?, FloatRange::parse_prefix))
+    }
+
+    /// Returns the value of the `level` argument, if specified
+    pub fn level(&self) -> Option<IntRange> {
+        self.arguments("level")
+            .find_map(|argument| parse_range(plain_value(argument)?, IntRange::parse_prefix))
+    }
+
+    /// Returns the objectives and ranges of all `scores` arguments
+    pub fn scores(&self) -> Vec<(&str, IntRange)> {
+        self.arguments("scores")
+            .filter_map(|argument| match &argument.value {
+                SelectorValue::Map(entries) => Some(entries),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|(objective, value)| match value {
+                SelectorValue::Plain(value) => Some((
+                    objective.as_str(),
+                    parse_range(value, IntRange::parse_prefix)?,
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn plain_value(argument: &SelectorArgument) -> Option<&str> {
+    match &argument.value {
+        SelectorValue::Plain(value) => Some(value),
+        SelectorValue::Map(_) | SelectorValue::Nbt(_) => None,
+    }
+}
+
+/// Parses `value` as a range, which has to span the whole value
+fn parse_range<T>(value: &str, parse: fn(&str) -> Result<(&str, T), &str>) -> Option<T> {
+    match parse(value) {
+        Ok(("", range)) => Some(range),
+        _ => None,
+    }
+}
+
+impl fmt::Display for TargetSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TargetSelector::Selector { kind, arguments } => {
+                write!(f, "{}", kind)?;
+                if !arguments.is_empty() {
+                    write!(f, "[")?;
+                    for (index, argument) in arguments.iter().enumerate() {
+                        if index > 0 {
+                            write!(f, ",")?;
+                        }
+                        write!(f, "{}", argument)?;
+                    }
+                    write!(f, "]")?;
+                }
+                Ok(())
+            }
+            TargetSelector::Player(name) => write!(f, "{}", name),
+            TargetSelector::Uuid(uuid) => write!(f, "{}", uuid),
+        }
+    }
+}
+
+impl CommandParse for TargetSelector {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, selector) = match value.strip_prefix('@') {
+            Some(selector) => {
+                let mut chars = selector.chars();
+                let kind = chars
+                    .next()
+                    .and_then(SelectorKind::from_char)
+                    .ok_or(value)?;
+                let rest = chars.as_str();
+                let (rest, arguments) = match rest.strip_prefix('[') {
+                    Some(arguments) => parse_arguments(arguments)?,
+                    None => (rest, Vec::new()),
+                };
+                (rest, TargetSelector::Selector { kind, arguments })
+            }
+            None if value.starts_with('[') => {
+                let (rest, uuid) = Uuid::parse_prefix(value)?;
+                (rest, TargetSelector::Uuid(uuid))
+            }
+            None => {
+                let (rest, word) = take_while(value, |c| !c.is_whitespace());
+                let selector = if let Some(uuid) = Uuid::parse_hyphenated(word) {
+                    TargetSelector::Uuid(uuid)
+                } else if is_player_name(word) {
+                    TargetSelector::Player(word.to_string())
+                } else {
+                    return Err(value);
+                };
+                (rest, selector)
+            }
+        };
+
+        let rest = strip_separator(rest)?;
+        Ok((rest, selector))
+    }
+}
+
+/// Parses the arguments of a selector after the opening bracket, up to and including the closing bracket
+fn parse_arguments(value: &str) -> Result<(&str, Vec<SelectorArgument>), &str> {
+    let mut arguments = Vec::new();
+    let mut rest = value.trim_start();
+    if let Some(rest) = rest.strip_prefix(']') {
+        return Ok((rest, arguments));
+    }
+
+    loop {
+        let (after_name, name) = take_while(rest, is_unquoted_char);
+        if !ARGUMENT_NAMES.contains(&name) {
+            return Err(rest);
+        }
+
+        let after_eq = after_name
+            .trim_start()
+            .strip_prefix('=')
+            .ok_or(after_name)?
+            .trim_start();
+        let (value_start, negated) = match after_eq.strip_prefix('!') {
+            Some(value_start) => (value_start.trim_start(), true),
+            None => (after_eq, false),
+        };
+        if negated && !NEGATABLE_ARGUMENT_NAMES.contains(&name) {
+            return Err(after_eq);
+        }
+
+        let (after_value, value) = if name == "nbt" {
+            let (after_value, compound) = Nbt::parse_compound(value_start)?;
+            (after_value, SelectorValue::Nbt(Nbt::Compound(compound)))
+        } else {
+            parse_value(value_start)?
+        };
+        let argument = SelectorArgument {
+            name: name.to_string(),
+            negated,
+            value,
+        };
+        if !is_valid_argument(&argument) {
+            return Err(value_start);
+        }
+        arguments.push(argument);
+
+        let after_value = after_value.trim_start();
+        if let Some(next) = after_value.strip_prefix(',') {
+            rest = next.trim_start();
+        } else if let Some(rest) = after_value.strip_prefix(']') {
+            return Ok((rest, arguments));
+        } else {
+            return Err(after_value);
+        }
+    }
+}
+
+fn is_valid_argument(argument: &SelectorArgument) -> bool {
+    match argument.name.as_str() {
+        "limit" => plain_value(argument)
+            .and_then(|value| value.parse::<i32>().ok())
+            .map_or(false, |limit| limit > 0),
+        "sort" => plain_value(argument)
+            .and_then(SelectorSort::from_str)
+            .is_some(),
+        "x" | "y" | "z" | "dx" | "dy" | "dz" => plain_value(argument)
+            .and_then(|value| value.parse::<f64>().ok())
+            .is_some(),
+        "distance" | "x_rotation" | "y_rotation" => plain_value(argument)
+            .and_then(|value| parse_range(value, FloatRange::parse_prefix))
+            .is_some(),
+        "level" => plain_value(argument)
+            .and_then(|value| parse_range(value, IntRange::parse_prefix))
+            .is_some(),
+        "scores" => match &argument.value {
+            SelectorValue::Map(entries) => entries.iter().all(|(_, value)| match value {
+                SelectorValue::Plain(value) => parse_range(value, IntRange::parse_prefix).is_some(),
+                _ => false,
+            }),
+            _ => false,
+        },
+        "advancements" => matches!(argument.value, SelectorValue::Map(_)),
+        "nbt" => matches!(argument.value, SelectorValue::Nbt(_)),
+        _ => matches!(argument.value, SelectorValue::Plain(_)),
+    }
+}
+
+/// Parses a single argument value, which may be a quoted string or a nested map
+fn parse_value(value: &str) -> Result<(&str, SelectorValue), &str> {
+    if let Some(map) = value.strip_prefix('{') {
+        let mut entries = Vec::new();
+        let mut rest = map.trim_start();
+        if let Some(rest) = rest.strip_prefix('}') {
+            return Ok((rest, SelectorValue::Map(entries)));
+        }
+
+        loop {
+            let (after_key, key) = take_while(rest, |c| {
+                !(c.is_whitespace() || c == '=' || c == ',' || c == '}')
+            });
+            if key.is_empty() {
+                return Err(rest);
+            }
+            let after_eq = after_key
+                .trim_start()
+                .strip_prefix('=')
+                .ok_or(after_key)?
+                .trim_start();
+            let (after_value, value) = parse_value(after_eq)?;
+            entries.push((key.to_string(), value));
+
+            let after_value = after_value.trim_start();
+            if let Some(next) = after_value.strip_prefix(',') {
+                rest = next.trim_start();
+            } else if let Some(rest) = after_value.strip_prefix('}') {
+                return Ok((rest, SelectorValue::Map(entries)));
+            } else {
+                return Err(after_value);
+            }
+        }
+    } else if value.starts_with('"') || value.starts_with('\'') {
+        let (rest, quoted) = scan_quoted(value)?;
+        Ok((rest, SelectorValue::Plain(quoted.to_string())))
+    } else {
+        let (rest, plain) = take_while(value, |c| {
+            !(c.is_whitespace() || c == ',' || c == ']' || c == '}')
+        });
+        Ok((rest, SelectorValue::Plain(plain.to_string())))
+    }
+}
+
+/// Scans a quoted string including its quotes and returns a tuple of `(rest, quoted)`
+fn scan_quoted(value: &str) -> Result<(&str, &str), &str> {
+    let quote = value.chars().next().ok_or(value)?;
+    let mut escaped = false;
+    for (index, c) in value.char_indices().skip(1) {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            let (quoted, rest) = value.split_at(index + c.len_utf8());
+            return Ok((rest, quoted));
+        }
+    }
+    Err(value)
+}
+
+fn is_player_name(value: &str) -> bool {
+    !value.is_empty() && value.len() <= 16 && value.chars().all(is_unquoted_char)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SelectorKind, SelectorSort, SelectorValue, TargetSelector};
+    use crate::{CommandParse, FloatRange, IntRange, Uuid};
+
+    #[test]
+    fn test_selector() {
+        let input = "@e[type=!zombie,distance=..5,scores={a=1..},tag=x,limit=3,sort=nearest]";
+        let (rest, selector) = TargetSelector::parse_from_command(input).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(selector.to_string(), input);
+        assert_eq!(selector.limit(), Some(3));
+        assert_eq!(selector.sort(), Some(SelectorSort::Nearest));
+        assert_eq!(
+            selector.distance(),
+            Some(FloatRange {
+                min: None,
+                max: Some(5.0)
+            })
+        );
+        assert_eq!(
+            selector.scores(),
+            vec![(
+                "a",
+                IntRange {
+                    min: Some(1),
+                    max: None
+                }
+            )]
+        );
+
+        let scores = selector.arguments("scores").next().unwrap();
+        assert_eq!(
+            scores.value,
+            SelectorValue::Map(vec![(
+                "a".to_string(),
+                SelectorValue::Plain("1..".to_string())
+            )])
+        );
+    }
+
+    #[test]
+    fn test_selector_with_spaces() {
+        let (rest, selector) =
+            TargetSelector::parse_from_command("@a[ name = \"Some Player\" , nbt={a:\"]\"}] foo")
+                .unwrap();
+        assert_eq!(rest, "foo");
+        assert_eq!(
+            selector.to_string(),
+            "@a[name=\"Some Player\",nbt={a:\"]\"}]"
+        );
+    }
+
+    #[test]
+    fn test_simple() {
+        assert_eq!(
+            TargetSelector::parse_from_command("@s foo"),
+            Ok((
+                "foo",
+                TargetSelector::Selector {
+                    kind: SelectorKind::Executor,
+                    arguments: Vec::new()
+                }
+            ))
+        );
+        assert_eq!(
+            TargetSelector::parse_from_command("Steve"),
+            Ok(("", TargetSelector::Player("Steve".to_string())))
+        );
+
+        let uuid = "f81d4fae-7dec-11d0-a765-00a0c91e6bf6";
+        let (_, selector) = TargetSelector::parse_from_command(uuid).unwrap();
+        assert_eq!(
+            selector,
+            TargetSelector::Uuid(Uuid(0xf81d4fae_7dec_11d0_a765_00a0c91e6bf6))
+        );
+        assert_eq!(selector.to_string(), uuid);
+        assert_eq!(
+            TargetSelector::parse_from_command("[I;0,0,0,1] foo"),
+            Ok(("foo", TargetSelector::Uuid(Uuid(1))))
+        );
+        assert_eq!(
+            TargetSelector::parse_from_command("0-0-0-0-1"),
+            Ok(("", TargetSelector::Uuid(Uuid(1))))
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(TargetSelector::parse_from_command("@x"), Err("@x"));
+        assert_eq!(
+            TargetSelector::parse_from_command("@e[foo=1]"),
+            Err("foo=1]")
+        );
+        assert_eq!(
+            TargetSelector::parse_from_command("@e[limit=!1]"),
+            Err("!1]")
+        );
+        assert_eq!(
+            TargetSelector::parse_from_command("@e[sort=foo]"),
+            Err("foo]")
+        );
+        assert_eq!(
+            TargetSelector::parse_from_command("@e[distance=5..1]"),
+            Err("5..1]")
+        );
+        assert_eq!(
+            TargetSelector::parse_from_command("@e[scores={a=x}]"),
+            Err("{a=x}]")
+        );
+        assert_eq!(TargetSelector::parse_from_command("@e[tag=a"), Err(""));
+        assert_eq!(TargetSelector::parse_from_command("@e[]x"), Err("x"));
+        assert_eq!(
+            TargetSelector::parse_from_command("ThisNameIsFarTooLong"),
+            Err("ThisNameIsFarTooLong")
+        );
+    }
+}
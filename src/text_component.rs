@@ -0,0 +1,680 @@
+use std::fmt;
+
+use crate::{strip_separator, take_while, CommandParse, Nbt};
+
+/// A json value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// The entries of an object, in the order they were written
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Parses a single json value and returns a tuple of `(rest, value)`
+    pub(crate) fn parse_value(value: &str) -> Result<(&str, Self), &str> {
+        if let Some(object) = value.strip_prefix('{') {
+            let mut entries = Vec::new();
+            let mut rest = object.trim_start();
+            if let Some(rest) = rest.strip_prefix('}') {
+                return Ok((rest, Json::Object(entries)));
+            }
+            loop {
+                let (after_key, key) = parse_string(rest)?;
+                let after_key = after_key.trim_start();
+                let after_colon = after_key.strip_prefix(':').ok_or(after_key)?.trim_start();
+                let (after_value, value) = Json::parse_value(after_colon)?;
+                entries.push((key, value));
+
+                let after_value = after_value.trim_start();
+                if let Some(next) = after_value.strip_prefix(',') {
+                    rest = next.trim_start();
+                } else if let Some(rest) = after_value.strip_prefix('}') {
+                    return Ok((rest, Json::Object(entries)));
+                } else {
+                    return Err(after_value);
+                }
+            }
+        } else if let Some(array) = value.strip_prefix('[') {
+            let mut values = Vec::new();
+            let mut rest = array.trim_start();
+            if let Some(rest) = rest.strip_prefix(']') {
+                return Ok((rest, Json::Array(values)));
+            }
+            loop {
+                let (after_value, value) = Json::parse_value(rest)?;
+                values.push(value);
+
+                let after_value = after_value.trim_start();
+                if let Some(next) = after_value.strip_prefix(',') {
+                    rest = next.trim_start();
+                } else if let Some(rest) = after_value.strip_prefix(']') {
+                    return Ok((rest, Json::Array(values)));
+                } else {
+                    return Err(after_value);
+                }
+            }
+        } else if value.starts_with('"') {
+            let (rest, string) = parse_string(value)?;
+            Ok((rest, Json::String(string)))
+        } else {
+            let (rest, word) = take_while(value, |c| {
+                c.is_ascii_alphanumeric() || c == '-' || c == '+' || c == '.'
+            });
+            let json = match word {
+                "null" => Json::Null,
+                "true" => Json::Bool(true),
+                "false" => Json::Bool(false),
+                _ if is_number(word) => Json::Number(word.parse().map_err(|_| value)?),
+                _ => return Err(value),
+            };
+            Ok((rest, json))
+        }
+    }
+
+    fn from_nbt(nbt: &Nbt) -> Json {
+        fn numbers<T: Copy + Into<f64>>(values: &[T]) -> Json {
+            Json::Array(
+                values
+                    .iter()
+                    .map(|value| Json::Number((*value).into()))
+                    .collect(),
+            )
+        }
+
+        match nbt {
+            // Snbt has no booleans, so `true` and `false` are stored as bytes
+            Nbt::Byte(0) => Json::Bool(false),
+            Nbt::Byte(1) => Json::Bool(true),
+            Nbt::Byte(value) => Json::Number((*value).into()),
+            Nbt::Short(value) => Json::Number((*value).into()),
+            Nbt::Int(value) => Json::Number((*value).into()),
+            Nbt::Long(value) => Json::Number(*value as f64),
+            Nbt::Float(value) => Json::Number((*value).into()),
+            Nbt::Double(value) => Json::Number(*value),
+            Nbt::String(value) => Json::String(value.clone()),
+            Nbt::List(values) => Json::Array(values.iter().map(Json::from_nbt).collect()),
+            Nbt::ByteArray(values) => numbers(values),
+            Nbt::IntArray(values) => numbers(values),
+            Nbt::LongArray(values) => Json::Array(
+                values
+                    .iter()
+                    .map(|value| Json::Number(*value as f64))
+                    .collect(),
+            ),
+            Nbt::Compound(compound) => Json::Object(
+                compound
+                    .iter()
+                    .map(|(key, value)| (key.clone(), Json::from_nbt(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn get<'a>(entries: &'a [(String, Json)], key: &str) -> Option<&'a Json> {
+        entries
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value)
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        match self {
+            Json::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(value) => write!(f, "{}", value),
+            Json::String(value) => write_string(f, value),
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(entries) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// Matches `-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][-+]?[0-9]+)?`
+fn is_number(value: &str) -> bool {
+    let digits = |value: &str| !value.is_empty() && value.chars().all(|c| c.is_ascii_digit());
+
+    let value = value.strip_prefix('-').unwrap_or(value);
+    let (mantissa, exponent) = match value.find(['e', 'E']) {
+        Some(index) => (&value[..index], Some(&value[index + 1..])),
+        None => (value, None),
+    };
+    let (integral, fractional) = match mantissa.split_once('.') {
+        Some((integral, fractional)) => (integral, Some(fractional)),
+        None => (mantissa, None),
+    };
+
+    digits(integral)
+        && (integral == "0" || !integral.starts_with('0'))
+        && fractional.map_or(true, digits)
+        && exponent.map_or(true, |exponent| {
+            digits(exponent.strip_prefix(['-', '+']).unwrap_or(exponent))
+        })
+}
+
+/// Parses a json string and returns a tuple of `(rest, string)`
+fn parse_string(value: &str) -> Result<(&str, String), &str> {
+    let mut chars = value.strip_prefix('"').ok_or(value)?.char_indices();
+    let mut string = String::new();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return Ok((&value[index + 2..], string)),
+            '\\' => {
+                let escaped = match chars.next() {
+                    Some((_, '"')) => '"',
+                    Some((_, '\\')) => '\\',
+                    Some((_, '/')) => '/',
+                    Some((_, 'b')) => '\u{8}',
+                    Some((_, 'f')) => '\u{c}',
+                    Some((_, 'n')) => '\n',
+                    Some((_, 'r')) => '\r',
+                    Some((_, 't')) => '\t',
+                    Some((_, 'u')) => {
+                        let start = index + 3;
+                        let code = value
+                            .get(start..start + 4)
+                            .and_then(|code| u32::from_str_radix(code, 16).ok())
+                            .ok_or(&value[index + 1..])?;
+                        for _ in 0..4 {
+                            chars.next();
+                        }
+                        // Surrogate pairs are written as two escapes
+                        let code = if (0xD800..0xDC00).contains(&code) {
+                            let low = value
+                                .get(start + 4..start + 10)
+                                .and_then(|low| low.strip_prefix("\\u"))
+                                .and_then(|low| u32::from_str_radix(low, 16).ok())
+                                .filter(|low| (0xDC00..0xE000).contains(low))
+                                .ok_or(&value[index + 1..])?;
+                            for _ in 0..6 {
+                                chars.next();
+                            }
+                            0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00)
+                        } else {
+                            code
+                        };
+                        char::from_u32(code).ok_or(&value[index + 1..])?
+                    }
+                    _ => return Err(&value[index + 1..]),
+                };
+                string.push(escaped);
+            }
+            c => string.push(c),
+        }
+    }
+    Err(value)
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+/// Where the value of a nbt text component is read from
+#[derive(Debug, Clone, PartialEq)]
+pub enum NbtSource {
+    Block(String),
+    Entity(String),
+    Storage(String),
+}
+
+/// The content of a [`TextComponent`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextContent {
+    Text(String),
+    Translate {
+        key: String,
+        with: Vec<TextComponent>,
+        fallback: Option<String>,
+    },
+    Score {
+        name: String,
+        objective: String,
+    },
+    Selector {
+        selector: String,
+        separator: Option<Box<TextComponent>>,
+    },
+    Keybind(String),
+    Nbt {
+        path: String,
+        source: NbtSource,
+        interpret: Option<bool>,
+        separator: Option<Box<TextComponent>>,
+    },
+}
+
+/// The formatting of a [`TextComponent`]. Unset values are inherited from the parent component.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TextStyle {
+    pub color: Option<String>,
+    pub font: Option<String>,
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub underlined: Option<bool>,
+    pub strikethrough: Option<bool>,
+    pub obfuscated: Option<bool>,
+    pub insertion: Option<String>,
+}
+
+/// A rich text component, as used by `tellraw` or `title`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextComponent {
+    pub content: TextContent,
+    pub style: TextStyle,
+    pub extra: Vec<TextComponent>,
+    /// All other keys, like `clickEvent` or `hoverEvent`, in the order they were written
+    pub other: Vec<(String, Json)>,
+}
+
+const CONTENT_KEYS: &[&str] = &[
+    "text",
+    "translate",
+    "with",
+    "fallback",
+    "score",
+    "selector",
+    "separator",
+    "keybind",
+    "nbt",
+    "block",
+    "entity",
+    "storage",
+    "interpret",
+];
+
+const STYLE_KEYS: &[&str] = &[
+    "color",
+    "font",
+    "bold",
+    "italic",
+    "underlined",
+    "strikethrough",
+    "obfuscated",
+    "insertion",
+];
+
+impl TextComponent {
+    pub fn text(text: impl Into<String>) -> Self {
+        TextComponent {
+            content: TextContent::Text(text.into()),
+            style: TextStyle::default(),
+            extra: Vec::new(),
+            other: Vec::new(),
+        }
+    }
+
+    /// Reads a component from its json representation
+    pub fn from_json(json: &Json) -> Option<Self> {
+        match json {
+            Json::String(text) => Some(TextComponent::text(text.as_str())),
+            Json::Bool(_) | Json::Number(_) => Some(TextComponent::text(json.to_string())),
+            Json::Array(components) => {
+                let (first, rest) = components.split_first()?;
+                let mut component = TextComponent::from_json(first)?;
+                for extra in rest {
+                    component.extra.push(TextComponent::from_json(extra)?);
+                }
+                Some(component)
+            }
+            Json::Object(entries) => Self::from_json_object(entries),
+            Json::Null => None,
+        }
+    }
+
+    fn from_json_object(entries: &[(String, Json)]) -> Option<Self> {
+        let get = |key| Json::get(entries, key);
+        // Absent keys yield `Some(None)`, keys of the wrong type yield `None`
+        let get_string = |key| match get(key) {
+            Some(value) => value.as_str().map(Some),
+            None => Some(None),
+        };
+        let get_bool = |key| match get(key) {
+            Some(value) => value.as_bool().map(Some),
+            None => Some(None),
+        };
+        let get_separator = || match get("separator") {
+            Some(separator) => {
+                TextComponent::from_json(separator).map(|separator| Some(Box::new(separator)))
+            }
+            None => Some(None),
+        };
+
+        let content = if let Some(text) = get("text") {
+            match text {
+                Json::String(text) => TextContent::Text(text.clone()),
+                Json::Bool(_) | Json::Number(_) => TextContent::Text(text.to_string()),
+                _ => return None,
+            }
+        } else if let Some(key) = get("translate") {
+            let with = match get("with") {
+                Some(Json::Array(with)) => with
+                    .iter()
+                    .map(TextComponent::from_json)
+                    .collect::<Option<_>>()?,
+                Some(_) => return None,
+                None => Vec::new(),
+            };
+            TextContent::Translate {
+                key: key.as_str()?.to_string(),
+                with,
+                fallback: get_string("fallback")?.map(str::to_string),
+            }
+        } else if let Some(Json::Object(score)) = get("score") {
+            TextContent::Score {
+                name: Json::get(score, "name")?.as_str()?.to_string(),
+                objective: Json::get(score, "objective")?.as_str()?.to_string(),
+            }
+        } else if let Some(selector) = get("selector") {
+            TextContent::Selector {
+                selector: selector.as_str()?.to_string(),
+                separator: get_separator()?,
+            }
+        } else if let Some(keybind) = get("keybind") {
+            TextContent::Keybind(keybind.as_str()?.to_string())
+        } else if let Some(path) = get("nbt") {
+            let source = if let Some(block) = get_string("block")? {
+                NbtSource::Block(block.to_string())
+            } else if let Some(entity) = get_string("entity")? {
+                NbtSource::Entity(entity.to_string())
+            } else {
+                NbtSource::Storage(get_string("storage")??.to_string())
+            };
+            TextContent::Nbt {
+                path: path.as_str()?.to_string(),
+                source,
+                interpret: get_bool("interpret")?,
+                separator: get_separator()?,
+            }
+        } else {
+            return None;
+        };
+
+        let style = TextStyle {
+            color: get_string("color")?.map(str::to_string),
+            font: get_string("font")?.map(str::to_string),
+            bold: get_bool("bold")?,
+            italic: get_bool("italic")?,
+            underlined: get_bool("underlined")?,
+            strikethrough: get_bool("strikethrough")?,
+            obfuscated: get_bool("obfuscated")?,
+            insertion: get_string("insertion")?.map(str::to_string),
+        };
+
+        let extra = match get("extra") {
+            Some(Json::Array(extra)) => extra
+                .iter()
+                .map(TextComponent::from_json)
+                .collect::<Option<_>>()?,
+            Some(_) => return None,
+            None => Vec::new(),
+        };
+
+        let other = entries
+            .iter()
+            .filter(|(key, _)| {
+                let key = key.as_str();
+                key != "extra" && !CONTENT_KEYS.contains(&key) && !STYLE_KEYS.contains(&key)
+            })
+            .cloned()
+            .collect();
+
+        Some(TextComponent {
+            content,
+            style,
+            extra,
+            other,
+        })
+    }
+
+    /// Converts this component to its most compact json representation
+    pub fn to_json(&self) -> Json {
+        if let TextContent::Text(text) = &self.content {
+            if self.style == TextStyle::default() && self.extra.is_empty() && self.other.is_empty()
+            {
+                return Json::String(text.clone());
+            }
+        }
+
+        let mut entries = Vec::new();
+        let mut push = |key: &str, value: Json| entries.push((key.to_string(), value));
+        let string = |value: &str| Json::String(value.to_string());
+
+        match &self.content {
+            TextContent::Text(text) => push("text", string(text)),
+            TextContent::Translate {
+                key,
+                with,
+                fallback,
+            } => {
+                push("translate", string(key));
+                if let Some(fallback) = fallback {
+                    push("fallback", string(fallback));
+                }
+                if !with.is_empty() {
+                    push(
+                        "with",
+                        Json::Array(with.iter().map(TextComponent::to_json).collect()),
+                    );
+                }
+            }
+            TextContent::Score { name, objective } => push(
+                "score",
+                Json::Object(vec![
+                    ("name".to_string(), string(name)),
+                    ("objective".to_string(), string(objective)),
+                ]),
+            ),
+            TextContent::Selector {
+                selector,
+                separator,
+            } => {
+                push("selector", string(selector));
+                if let Some(separator) = separator {
+                    push("separator", separator.to_json());
+                }
+            }
+            TextContent::Keybind(keybind) => push("keybind", string(keybind)),
+            TextContent::Nbt {
+                path,
+                source,
+                interpret,
+                separator,
+            } => {
+                push("nbt", string(path));
+                match source {
+                    NbtSource::Block(block) => push("block", string(block)),
+                    NbtSource::Entity(entity) => push("entity", string(entity)),
+                    NbtSource::Storage(storage) => push("storage", string(storage)),
+                }
+                if let Some(interpret) = interpret {
+                    push("interpret", Json::Bool(*interpret));
+                }
+                if let Some(separator) = separator {
+                    push("separator", separator.to_json());
+                }
+            }
+        }
+
+        let style = &self.style;
+        for (key, value) in &[
+            ("color", &style.color),
+            ("font", &style.font),
+            ("insertion", &style.insertion),
+        ] {
+            if let Some(value) = value {
+                push(key, string(value));
+            }
+        }
+        for (key, value) in &[
+            ("bold", style.bold),
+            ("italic", style.italic),
+            ("underlined", style.underlined),
+            ("strikethrough", style.strikethrough),
+            ("obfuscated", style.obfuscated),
+        ] {
+            if let Some(value) = value {
+                push(key, Json::Bool(*value));
+            }
+        }
+
+        for (key, value) in &self.other {
+            push(key, value.clone());
+        }
+        if !self.extra.is_empty() {
+            push(
+                "extra",
+                Json::Array(self.extra.iter().map(TextComponent::to_json).collect()),
+            );
+        }
+        Json::Object(entries)
+    }
+}
+
+impl fmt::Display for TextComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_json())
+    }
+}
+
+impl CommandParse for TextComponent {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        // Newer versions also accept components written as snbt
+        let (rest, json) = match Json::parse_value(value) {
+            Ok(json) => json,
+            Err(error) => match Nbt::parse_value(value) {
+                Ok((rest, nbt)) => (rest, Json::from_nbt(&nbt)),
+                Err(_) => return Err(error),
+            },
+        };
+        let component = TextComponent::from_json(&json).ok_or(value)?;
+        let rest = strip_separator(rest)?;
+        Ok((rest, component))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Json, TextComponent, TextContent};
+    use crate::CommandParse;
+
+    fn round_trip(input: &str) -> String {
+        let (rest, component) = TextComponent::parse_from_command(input).unwrap();
+        assert_eq!(rest, "");
+        component.to_string()
+    }
+
+    #[test]
+    fn test_json() {
+        let (rest, json) =
+            Json::parse_value("{\"a\": [1, -2.5e3, true, null], \"b\\u00e4\": \"\\\"\"} foo")
+                .unwrap();
+        assert_eq!(rest, " foo");
+        assert_eq!(
+            json.to_string(),
+            "{\"a\":[1,-2500,true,null],\"bä\":\"\\\"\"}"
+        );
+        assert_eq!(Json::parse_value("01"), Err("01"));
+        assert_eq!(Json::parse_value("[1,]"), Err("]"));
+    }
+
+    #[test]
+    fn test_text_component() {
+        let input = "{\"text\":\"Hello\",\"color\":\"red\",\"bold\":true,\"extra\":[{\"selector\":\"@p\"},\"!\"]}";
+        let (rest, component) = TextComponent::parse_from_command(input).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(component.content, TextContent::Text("Hello".to_string()));
+        assert_eq!(component.style.bold, Some(true));
+        assert_eq!(component.extra[1], TextComponent::text("!"));
+        assert_eq!(component.to_string(), input);
+    }
+
+    #[test]
+    fn test_compact() {
+        assert_eq!(round_trip("\"hi\""), "\"hi\"");
+        assert_eq!(round_trip("{ \"text\" : \"hi\" }"), "\"hi\"");
+        assert_eq!(
+            round_trip("[\"a\", \"b\"]"),
+            "{\"text\":\"a\",\"extra\":[\"b\"]}"
+        );
+        assert_eq!(
+            round_trip("{\"score\":{\"name\":\"@s\",\"objective\":\"x\"},\"clickEvent\":{\"action\":\"run_command\",\"value\":\"/say\"}}"),
+            "{\"score\":{\"name\":\"@s\",\"objective\":\"x\"},\"clickEvent\":{\"action\":\"run_command\",\"value\":\"/say\"}}"
+        );
+        assert_eq!(
+            round_trip("{\"translate\":\"chat.type\",\"with\":[{\"keybind\":\"key.jump\"}]}"),
+            "{\"translate\":\"chat.type\",\"with\":[{\"keybind\":\"key.jump\"}]}"
+        );
+    }
+
+    #[test]
+    fn test_snbt() {
+        assert_eq!(
+            round_trip("{text:'hi',italic:0b}"),
+            "{\"text\":\"hi\",\"italic\":false}"
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            TextComponent::parse_from_command("{\"color\":\"red\"}"),
+            Err("{\"color\":\"red\"}")
+        );
+        assert_eq!(
+            TextComponent::parse_from_command("{\"text\":\"a\"} x"),
+            Ok(("x", TextComponent::text("a")))
+        );
+        assert!(TextComponent::parse_from_command("{\"text\":\"a\"").is_err());
+    }
+}
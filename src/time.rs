@@ -0,0 +1,95 @@
+use std::fmt;
+
+use crate::{strip_separator, take_while, CommandParse};
+
+const TICKS_PER_SECOND: u32 = 20;
+const TICKS_PER_DAY: u32 = 24000;
+
+/// A duration in game ticks, written like `10`, `10t`, `0.5s` or `1d`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Ticks(pub u32);
+
+impl Ticks {
+    /// Parses a duration at the start of `value` and returns a tuple of `(rest, ticks)`.
+    /// Fractional durations are rounded to the nearest tick.
+    pub(crate) fn parse_prefix(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, number) = take_while(value, |c| c.is_ascii_digit() || c == '.' || c == '-');
+        let number: f64 = number.parse().map_err(|_| value)?;
+
+        let (rest, factor) = match rest.chars().next() {
+            Some('t') => (&rest[1..], 1),
+            Some('s') => (&rest[1..], TICKS_PER_SECOND),
+            Some('d') => (&rest[1..], TICKS_PER_DAY),
+            _ => (rest, 1),
+        };
+
+        let ticks = (number * f64::from(factor)).round();
+        if !(0.0..=f64::from(u32::MAX)).contains(&ticks) {
+            return Err(value);
+        }
+        Ok((rest, Ticks(ticks as u32)))
+    }
+}
+
+impl fmt::Display for Ticks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ticks = self.0;
+        if ticks != 0 && ticks % TICKS_PER_DAY == 0 {
+            write!(f, "{}d", ticks / TICKS_PER_DAY)
+        } else if ticks != 0 && ticks % TICKS_PER_SECOND == 0 {
+            write!(f, "{}s", ticks / TICKS_PER_SECOND)
+        } else {
+            write!(f, "{}", ticks)
+        }
+    }
+}
+
+impl CommandParse for Ticks {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, ticks) = Self::parse_prefix(value)?;
+        let rest = strip_separator(rest)?;
+        Ok((rest, ticks))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Ticks;
+    use crate::CommandParse;
+
+    #[test]
+    fn test_ticks() {
+        assert_eq!(
+            Ticks::parse_from_command("10s foo"),
+            Ok(("foo", Ticks(200)))
+        );
+        assert_eq!(Ticks::parse_from_command("5t"), Ok(("", Ticks(5))));
+        assert_eq!(Ticks::parse_from_command("7"), Ok(("", Ticks(7))));
+        assert_eq!(Ticks::parse_from_command("1d"), Ok(("", Ticks(24000))));
+        assert_eq!(Ticks::parse_from_command("0.5s"), Ok(("", Ticks(10))));
+        assert_eq!(Ticks::parse_from_command(".125d"), Ok(("", Ticks(3000))));
+    }
+
+    #[test]
+    fn test_display() {
+        for (input, output) in &[
+            ("0", "0"),
+            ("10t", "10"),
+            ("40", "2s"),
+            ("0.5s", "10"),
+            ("48000t", "2d"),
+            ("1.5d", "1800s"),
+        ] {
+            let (_, ticks) = Ticks::parse_from_command(input).unwrap();
+            assert_eq!(&ticks.to_string(), output);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(Ticks::parse_from_command("-1s"), Err("-1s"));
+        assert_eq!(Ticks::parse_from_command("s"), Err("s"));
+        assert_eq!(Ticks::parse_from_command("1m"), Err("m"));
+        assert_eq!(Ticks::parse_from_command("1.2.3"), Err("1.2.3"));
+    }
+}
@@ -0,0 +1,139 @@
+use std::fmt;
+
+use crate::{strip_separator, take_while, CommandParse, Nbt};
+
+/// A uuid, written like `f81d4fae-7dec-11d0-a765-00a0c91e6bf6`, in the loose form `0-0-0-0-1`
+/// or as an int array like `[I;-132296786,2112623056,-1486552928,-920753162]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Uuid(pub u128);
+
+impl Uuid {
+    /// Converts four ints, most significant first, to a uuid. This is how uuids are stored in nbt.
+    pub fn from_int_array(ints: [i32; 4]) -> Self {
+        let uuid = ints
+            .iter()
+            .fold(0, |uuid, int| (uuid << 32) | u128::from(*int as u32));
+        Uuid(uuid)
+    }
+
+    pub fn to_int_array(self) -> [i32; 4] {
+        let mut ints = [0; 4];
+        for (index, int) in ints.iter_mut().enumerate() {
+            *int = (self.0 >> (96 - index * 32)) as u32 as i32;
+        }
+        ints
+    }
+
+    /// Parses the hyphenated form of a uuid, where groups may be shorter than usual like in `0-0-0-0-1`
+    pub(crate) fn parse_hyphenated(value: &str) -> Option<Self> {
+        let groups: Vec<&str> = value.split('-').collect();
+        let lengths = [8, 4, 4, 4, 12];
+        if groups.len() != lengths.len() {
+            return None;
+        }
+
+        let mut uuid = 0;
+        for (group, length) in groups.iter().zip(lengths.iter()) {
+            if group.is_empty()
+                || group.len() > *length
+                || !group.chars().all(|c| c.is_ascii_hexdigit())
+            {
+                return None;
+            }
+            uuid = (uuid << (length * 4)) | u128::from_str_radix(group, 16).ok()?;
+        }
+        Some(Uuid(uuid))
+    }
+
+    /// Parses a uuid at the start of `value` and returns a tuple of `(rest, uuid)`
+    pub(crate) fn parse_prefix(value: &str) -> Result<(&str, Self), &str> {
+        if value.starts_with('[') {
+            return match Nbt::parse_value(value) {
+                Ok((rest, Nbt::IntArray(ints))) if ints.len() == 4 => Ok((
+                    rest,
+                    Uuid::from_int_array([ints[0], ints[1], ints[2], ints[3]]),
+                )),
+                _ => Err(value),
+            };
+        }
+
+        let (rest, uuid) = take_while(value, |c| c.is_ascii_hexdigit() || c == '-');
+        let uuid = Uuid::parse_hyphenated(uuid).ok_or(value)?;
+        Ok((rest, uuid))
+    }
+}
+
+impl fmt::Display for Uuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex = format!("{:032x}", self.0);
+        write!(
+            f,
+            "{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        )
+    }
+}
+
+impl From<Uuid> for Nbt {
+    fn from(uuid: Uuid) -> Self {
+        Nbt::IntArray(uuid.to_int_array().to_vec())
+    }
+}
+
+impl CommandParse for Uuid {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, uuid) = Self::parse_prefix(value)?;
+        let rest = strip_separator(rest)?;
+        Ok((rest, uuid))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Uuid;
+    use crate::{CommandParse, Nbt};
+
+    const UUID: Uuid = Uuid(0xf81d4fae_7dec_11d0_a765_00a0c91e6bf6);
+
+    #[test]
+    fn test_uuid() {
+        assert_eq!(
+            Uuid::parse_from_command("f81d4fae-7dec-11d0-a765-00a0c91e6bf6 foo"),
+            Ok(("foo", UUID))
+        );
+        assert_eq!(Uuid::parse_from_command("0-0-0-0-1"), Ok(("", Uuid(1))));
+        assert_eq!(
+            Uuid::parse_from_command("1-2-3-4-5").map(|(_, uuid)| uuid.to_string()),
+            Ok("00000001-0002-0003-0004-000000000005".to_string())
+        );
+        assert_eq!(UUID.to_string(), "f81d4fae-7dec-11d0-a765-00a0c91e6bf6");
+    }
+
+    #[test]
+    fn test_int_array() {
+        let ints = [-132296786, 2112623056, -1486552928, -920753162];
+        assert_eq!(UUID.to_int_array(), ints);
+        assert_eq!(Uuid::from_int_array(ints), UUID);
+        assert_eq!(
+            Uuid::parse_from_command("[I; -132296786, 2112623056, -1486552928, -920753162] foo"),
+            Ok(("foo", UUID))
+        );
+        assert_eq!(Nbt::from(Uuid(1)).to_string(), "[I;0,0,0,1]");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(Uuid::parse_from_command("0-0-0-1"), Err("0-0-0-1"));
+        assert_eq!(Uuid::parse_from_command("0-0-0-0-"), Err("0-0-0-0-"));
+        assert_eq!(
+            Uuid::parse_from_command("0-00000-0-0-1"),
+            Err("0-00000-0-0-1")
+        );
+        assert_eq!(Uuid::parse_from_command("[I;1,2,3]"), Err("[I;1,2,3]"));
+        assert_eq!(Uuid::parse_from_command("0-0-0-0-1x"), Err("x"));
+    }
+}
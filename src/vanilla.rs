@@ -0,0 +1,468 @@
+//! Small argument types with a fixed vocabulary, like gamemodes or scoreboard operations
+
+use std::fmt;
+
+use crate::{strip_separator, take_while, CommandParse, ResourceLocation};
+
+/// Splits off the word at the start of `value` and returns a tuple of `(rest, word)`
+fn parse_word(value: &str) -> (&str, &str) {
+    take_while(value, |c| c != ' ')
+}
+
+/// Declares a fieldless enum where every variant is written as a single keyword
+macro_rules! keyword_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident => $keyword:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+        }
+
+        impl $name {
+            pub const ALL: &'static [$name] = &[$($name::$variant),*];
+
+            pub fn keyword(self) -> &'static str {
+                match self {
+                    $($name::$variant => $keyword,)*
+                }
+            }
+
+            pub fn from_keyword(keyword: &str) -> Option<Self> {
+                match keyword {
+                    $($keyword => Some($name::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.keyword())
+            }
+        }
+
+        impl CommandParse for $name {
+            fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+                let (rest, word) = parse_word(value);
+                let parsed = Self::from_keyword(word).ok_or(value)?;
+                let rest = strip_separator(rest)?;
+                Ok((rest, parsed))
+            }
+        }
+    };
+}
+
+keyword_enum! {
+    /// The operation of `scoreboard players operation`
+    pub enum ScoreboardOperation {
+        Assign => "=",
+        Add => "+=",
+        Subtract => "-=",
+        Multiply => "*=",
+        Divide => "/=",
+        Modulo => "%=",
+        Min => "<",
+        Max => ">",
+        Swap => "><",
+    }
+}
+
+keyword_enum! {
+    /// The part of an entity that `anchored` or `facing entity` refers to
+    pub enum EntityAnchor {
+        Eyes => "eyes",
+        Feet => "feet",
+    }
+}
+
+keyword_enum! {
+    pub enum Gamemode {
+        Survival => "survival",
+        Creative => "creative",
+        Adventure => "adventure",
+        Spectator => "spectator",
+    }
+}
+
+keyword_enum! {
+    pub enum Difficulty {
+        Peaceful => "peaceful",
+        Easy => "easy",
+        Normal => "normal",
+        Hard => "hard",
+    }
+}
+
+keyword_enum! {
+    /// A chat or team color
+    pub enum Color {
+        Black => "black",
+        DarkBlue => "dark_blue",
+        DarkGreen => "dark_green",
+        DarkAqua => "dark_aqua",
+        DarkRed => "dark_red",
+        DarkPurple => "dark_purple",
+        Gold => "gold",
+        Gray => "gray",
+        DarkGray => "dark_gray",
+        Blue => "blue",
+        Green => "green",
+        Aqua => "aqua",
+        Red => "red",
+        LightPurple => "light_purple",
+        Yellow => "yellow",
+        White => "white",
+        /// Resets to the default color
+        Reset => "reset",
+    }
+}
+
+keyword_enum! {
+    /// A heightmap, as used by `execute positioned over`
+    pub enum Heightmap {
+        WorldSurface => "world_surface",
+        MotionBlocking => "motion_blocking",
+        MotionBlockingNoLeaves => "motion_blocking_no_leaves",
+        OceanFloor => "ocean_floor",
+    }
+}
+
+/// A set of axes, like `xz` in `execute align xz`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Swizzle {
+    pub x: bool,
+    pub y: bool,
+    pub z: bool,
+}
+
+impl fmt::Display for Swizzle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (enabled, axis) in &[(self.x, 'x'), (self.y, 'y'), (self.z, 'z')] {
+            if *enabled {
+                write!(f, "{}", axis)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CommandParse for Swizzle {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, word) = parse_word(value);
+        if word.is_empty() {
+            return Err(value);
+        }
+
+        let mut swizzle = Swizzle::default();
+        for (index, c) in word.char_indices() {
+            let axis = match c {
+                'x' => &mut swizzle.x,
+                'y' => &mut swizzle.y,
+                'z' => &mut swizzle.z,
+                _ => return Err(&value[index..]),
+            };
+            // Every axis may only be specified once
+            if *axis {
+                return Err(&value[index..]);
+            }
+            *axis = true;
+        }
+
+        let rest = strip_separator(rest)?;
+        Ok((rest, swizzle))
+    }
+}
+
+/// An inventory slot, like `weapon.mainhand` or `container.5`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Slot {
+    Container(u8),
+    Hotbar(u8),
+    Inventory(u8),
+    EnderChest(u8),
+    Villager(u8),
+    Horse(u8),
+    PlayerCrafting(u8),
+    WeaponMainhand,
+    WeaponOffhand,
+    ArmorHead,
+    ArmorChest,
+    ArmorLegs,
+    ArmorFeet,
+    ArmorBody,
+    HorseSaddle,
+    HorseChest,
+    PlayerCursor,
+    Contents,
+}
+
+type IndexedSlot = fn(u8) -> Slot;
+
+/// The prefix, constructor and highest index of every indexed slot
+const INDEXED_SLOTS: &[(&str, IndexedSlot, u8)] = &[
+    ("container", Slot::Container, 53),
+    ("hotbar", Slot::Hotbar, 8),
+    ("inventory", Slot::Inventory, 26),
+    ("enderchest", Slot::EnderChest, 26),
+    ("villager", Slot::Villager, 7),
+    ("horse", Slot::Horse, 14),
+    ("player.crafting", Slot::PlayerCrafting, 3),
+];
+
+const NAMED_SLOTS: &[(&str, Slot)] = &[
+    ("weapon.mainhand", Slot::WeaponMainhand),
+    ("weapon.offhand", Slot::WeaponOffhand),
+    ("armor.head", Slot::ArmorHead),
+    ("armor.chest", Slot::ArmorChest),
+    ("armor.legs", Slot::ArmorLegs),
+    ("armor.feet", Slot::ArmorFeet),
+    ("armor.body", Slot::ArmorBody),
+    ("horse.saddle", Slot::HorseSaddle),
+    ("horse.chest", Slot::HorseChest),
+    ("player.cursor", Slot::PlayerCursor),
+    ("contents", Slot::Contents),
+];
+
+impl Slot {
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name == "weapon" {
+            return Some(Slot::WeaponMainhand);
+        }
+        if let Some((_, slot)) = NAMED_SLOTS.iter().find(|(slot_name, _)| *slot_name == name) {
+            return Some(*slot);
+        }
+
+        let (prefix, index) = name.rsplit_once('.')?;
+        let (_, slot, max) = INDEXED_SLOTS
+            .iter()
+            .find(|(slot_prefix, _, _)| *slot_prefix == prefix)?;
+        // Vanilla does not accept a sign or leading zeros
+        if index.starts_with('+') || (index.len() > 1 && index.starts_with('0')) {
+            return None;
+        }
+        let index: u8 = index.parse().ok()?;
+        if index > *max {
+            return None;
+        }
+        Some(slot(index))
+    }
+}
+
+impl fmt::Display for Slot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (prefix, index) = match self {
+            Slot::Container(index) => ("container", index),
+            Slot::Hotbar(index) => ("hotbar", index),
+            Slot::Inventory(index) => ("inventory", index),
+            Slot::EnderChest(index) => ("enderchest", index),
+            Slot::Villager(index) => ("villager", index),
+            Slot::Horse(index) => ("horse", index),
+            Slot::PlayerCrafting(index) => ("player.crafting", index),
+            named => {
+                let (name, _) = NAMED_SLOTS
+                    .iter()
+                    .find(|(_, slot)| slot == named)
+                    .expect("Every named slot has a name");
+                return write!(f, "{}", name);
+            }
+        };
+        write!(f, "{}.{}", prefix, index)
+    }
+}
+
+impl CommandParse for Slot {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, word) = parse_word(value);
+        let slot = Slot::from_name(word).ok_or(value)?;
+        let rest = strip_separator(rest)?;
+        Ok((rest, slot))
+    }
+}
+
+/// The criterion of a scoreboard objective, like `dummy`, `teamkill.red` or `minecraft.mined:minecraft.stone`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ObjectiveCriterion {
+    Dummy,
+    Trigger,
+    DeathCount,
+    PlayerKillCount,
+    TotalKillCount,
+    Health,
+    Xp,
+    Level,
+    Food,
+    Air,
+    Armor,
+    TeamKill(Color),
+    KilledByTeam(Color),
+    /// A statistic, like `minecraft.mined:minecraft.stone`
+    Statistic {
+        kind: ResourceLocation,
+        value: ResourceLocation,
+    },
+}
+
+const SIMPLE_CRITERIA: &[(&str, ObjectiveCriterion)] = &[
+    ("dummy", ObjectiveCriterion::Dummy),
+    ("trigger", ObjectiveCriterion::Trigger),
+    ("deathCount", ObjectiveCriterion::DeathCount),
+    ("playerKillCount", ObjectiveCriterion::PlayerKillCount),
+    ("totalKillCount", ObjectiveCriterion::TotalKillCount),
+    ("health", ObjectiveCriterion::Health),
+    ("xp", ObjectiveCriterion::Xp),
+    ("level", ObjectiveCriterion::Level),
+    ("food", ObjectiveCriterion::Food),
+    ("air", ObjectiveCriterion::Air),
+    ("armor", ObjectiveCriterion::Armor),
+];
+
+impl ObjectiveCriterion {
+    pub fn from_name(name: &str) -> Option<Self> {
+        if let Some((_, criterion)) = SIMPLE_CRITERIA
+            .iter()
+            .find(|(criterion_name, _)| *criterion_name == name)
+        {
+            return Some(criterion.clone());
+        }
+
+        let team_color = |color| Color::from_keyword(color).filter(|color| *color != Color::Reset);
+        if let Some(color) = name.strip_prefix("teamkill.") {
+            return team_color(color).map(ObjectiveCriterion::TeamKill);
+        }
+        if let Some(color) = name.strip_prefix("killedByTeam.") {
+            return team_color(color).map(ObjectiveCriterion::KilledByTeam);
+        }
+
+        let (kind, value) = name.split_once(':')?;
+        Some(ObjectiveCriterion::Statistic {
+            kind: parse_dotted_location(kind)?,
+            value: parse_dotted_location(value)?,
+        })
+    }
+}
+
+/// Parses a resource location which separates the namespace with a dot, like `minecraft.stone`
+fn parse_dotted_location(value: &str) -> Option<ResourceLocation> {
+    match value.split_once('.') {
+        Some((namespace, path)) => ResourceLocation::new(&format!("{}:{}", namespace, path)).ok(),
+        None => ResourceLocation::new(value).ok(),
+    }
+}
+
+impl fmt::Display for ObjectiveCriterion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjectiveCriterion::TeamKill(color) => write!(f, "teamkill.{}", color),
+            ObjectiveCriterion::KilledByTeam(color) => write!(f, "killedByTeam.{}", color),
+            ObjectiveCriterion::Statistic { kind, value } => write!(
+                f,
+                "{}.{}:{}.{}",
+                kind.namespace, kind.path, value.namespace, value.path
+            ),
+            simple => {
+                let (name, _) = SIMPLE_CRITERIA
+                    .iter()
+                    .find(|(_, criterion)| criterion == simple)
+                    .expect("Every simple criterion has a name");
+                write!(f, "{}", name)
+            }
+        }
+    }
+}
+
+impl CommandParse for ObjectiveCriterion {
+    fn parse_from_command(value: &str) -> Result<(&str, Self), &str> {
+        let (rest, word) = parse_word(value);
+        let criterion = ObjectiveCriterion::from_name(word).ok_or(value)?;
+        let rest = strip_separator(rest)?;
+        Ok((rest, criterion))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Color, Gamemode, ObjectiveCriterion, ScoreboardOperation, Slot, Swizzle};
+    use crate::{CommandParse, ResourceLocation};
+
+    #[test]
+    fn test_keyword_enums() {
+        assert_eq!(
+            ScoreboardOperation::parse_from_command(">< @s foo"),
+            Ok(("@s foo", ScoreboardOperation::Swap))
+        );
+        assert_eq!(
+            Gamemode::parse_from_command("creative"),
+            Ok(("", Gamemode::Creative))
+        );
+        assert_eq!(Color::DarkAqua.to_string(), "dark_aqua");
+        assert_eq!(Gamemode::ALL.len(), 4);
+        assert_eq!(Gamemode::parse_from_command("creativ"), Err("creativ"));
+    }
+
+    #[test]
+    fn test_swizzle() {
+        assert_eq!(
+            Swizzle::parse_from_command("zx ~ ~ ~"),
+            Ok((
+                "~ ~ ~",
+                Swizzle {
+                    x: true,
+                    y: false,
+                    z: true
+                }
+            ))
+        );
+        let (_, swizzle) = Swizzle::parse_from_command("zyx").unwrap();
+        assert_eq!(swizzle.to_string(), "xyz");
+        assert_eq!(Swizzle::parse_from_command("xyx"), Err("x"));
+        assert_eq!(Swizzle::parse_from_command("xw"), Err("w"));
+    }
+
+    #[test]
+    fn test_slot() {
+        assert_eq!(
+            Slot::parse_from_command("container.5 stone"),
+            Ok(("stone", Slot::Container(5)))
+        );
+        assert_eq!(
+            Slot::parse_from_command("weapon"),
+            Ok(("", Slot::WeaponMainhand))
+        );
+        assert_eq!(Slot::PlayerCrafting(2).to_string(), "player.crafting.2");
+        assert_eq!(Slot::ArmorHead.to_string(), "armor.head");
+        assert_eq!(Slot::parse_from_command("hotbar.9"), Err("hotbar.9"));
+        assert_eq!(Slot::parse_from_command("hotbar.01"), Err("hotbar.01"));
+    }
+
+    #[test]
+    fn test_objective_criterion() {
+        for criterion in &[
+            "dummy",
+            "deathCount",
+            "teamkill.red",
+            "minecraft.mined:minecraft.stone",
+        ] {
+            let (_, parsed) = ObjectiveCriterion::parse_from_command(criterion).unwrap();
+            assert_eq!(&parsed.to_string(), criterion);
+        }
+        assert_eq!(
+            ObjectiveCriterion::parse_from_command("used:diamond_pickaxe"),
+            Ok((
+                "",
+                ObjectiveCriterion::Statistic {
+                    kind: ResourceLocation::new("used").unwrap(),
+                    value: ResourceLocation::new("diamond_pickaxe").unwrap(),
+                }
+            ))
+        );
+        assert_eq!(
+            ObjectiveCriterion::parse_from_command("teamkill.reset"),
+            Err("teamkill.reset")
+        );
+    }
+}
@@ -6,5 +6,28 @@ fn tests() {
     t.pass("tests/test_files/multiple_options.rs");
     t.pass("tests/test_files/default_args.rs");
     t.pass("tests/test_files/captures.rs");
+    t.pass("tests/test_files/enum_variants.rs");
+    t.pass("tests/test_files/custom_variants.rs");
+    t.pass("tests/test_files/fallback.rs");
+    t.pass("tests/test_files/parser_config.rs");
+    t.pass("tests/test_files/nested_modules.rs");
+    t.pass("tests/test_files/include.rs");
+    t.pass("tests/test_files/execute.rs");
+    t.pass("tests/test_files/field_args.rs");
+    t.pass("tests/test_files/generics.rs");
+    t.pass("tests/test_files/derive_enum.rs");
+    t.pass("tests/test_files/derive_struct.rs");
     t.compile_fail("tests/test_files/no_enum.rs");
+    t.compile_fail("tests/test_files/enum_variant_without_attribute.rs");
+    t.compile_fail("tests/test_files/custom_variant_fields.rs");
+    t.compile_fail("tests/test_files/custom_variant_errors.rs");
+    t.compile_fail("tests/test_files/parser_config_errors.rs");
+    t.compile_fail("tests/test_files/include_conflict.rs");
+    t.compile_fail("tests/test_files/include_duplicate.rs");
+    t.compile_fail("tests/test_files/field_args_errors.rs");
+    t.compile_fail("tests/test_files/template_bindings.rs");
+    t.compile_fail("tests/test_files/template_errors.rs");
+    t.compile_fail("tests/test_files/generics_errors.rs");
+    t.compile_fail("tests/test_files/derive_enum_fields.rs");
+    t.compile_fail("tests/test_files/derive_struct_errors.rs");
 }
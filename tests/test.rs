@@ -47,6 +47,15 @@ where
     }
 }
 
+#[derive(command_parser::CommandParse, PartialEq, Eq, Debug)]
+#[command(rename_all = "lowercase")]
+pub enum Mode {
+    Survival,
+    Creative,
+    #[command(rename = "sp")]
+    Spectator,
+}
+
 #[parser]
 mod my_module {
     #[derive(PartialEq, Eq, Debug)]
@@ -74,6 +83,13 @@ mod my_module {
         pub my_int: super::MyInt,
     }
 
+    #[parse("gamemode $mode $player")]
+    #[derive(PartialEq, Eq, Debug)]
+    pub struct Gamemode {
+        pub mode: super::Mode,
+        pub player: String,
+    }
+
     #[parse("gamerule $foo", bar=OptionalValue(None))]
     #[parse("gamerule $foo $bar")]
     #[derive(Debug, PartialEq, Eq)]
@@ -116,5 +132,16 @@ fn test_command_macro() {
     assert_eq!(multiple_options_b.to_string(), "Short version: 23874 15");
 
     let baz: Command = "gamerule foo".parse().unwrap();
-    assert_eq!(baz.to_string(), "gamerule foo")
+    assert_eq!(baz.to_string(), "gamerule foo");
+
+    let gamemode: Command = "gamemode sp Steve".parse().unwrap();
+    assert_eq!(
+        gamemode,
+        Command::Gamemode(my_module::Gamemode {
+            mode: Mode::Spectator,
+            player: "Steve".to_string()
+        })
+    );
+    assert_eq!(gamemode.to_string(), "gamemode sp Steve");
+    assert!("gamemode Creative Steve".parse::<Command>().is_err());
 }
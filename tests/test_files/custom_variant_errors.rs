@@ -0,0 +1,22 @@
+use command_parser::parser;
+
+fn parse(input: &str) -> Result<(&str, String), &str> {
+    Ok(("", input.to_string()))
+}
+
+#[parser]
+mod my_module {
+    pub enum Command {
+        #[custom]
+        Pair(i32, i32),
+        #[custom(parse = super::parse)]
+        Raw(String),
+        #[custom]
+        #[parse("say $0")]
+        Say(String),
+        #[parse("reload $0")]
+        Reload { delay: i32 },
+    }
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use command_parser::parser;
+
+#[parser]
+mod my_module {
+    pub enum Command {
+        #[custom]
+        Pair(i32, i32),
+    }
+}
+
+fn main() {}
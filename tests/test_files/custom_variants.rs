@@ -0,0 +1,46 @@
+use std::fmt;
+
+use command_parser::{parse_command, parser};
+
+/// Keeps the whole remaining input
+fn parse_raw(value: &str) -> Result<(&str, String), &str> {
+    Ok(("", value.to_string()))
+}
+
+fn display_raw(value: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", value)
+}
+
+#[parser]
+mod my_module {
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Command {
+        #[parse("say $0")]
+        Say(String),
+        #[custom]
+        Number(i32),
+        #[custom(parse_with = super::parse_raw, display_with = super::display_raw)]
+        Raw(String),
+    }
+}
+
+use my_module::Command;
+
+fn main() {
+    assert_eq!(
+        parse_command::<Command>("say hi"),
+        Ok(Command::Say("hi".to_string()))
+    );
+    assert_eq!(parse_command::<Command>("42"), Ok(Command::Number(42)));
+    assert_eq!(Command::Number(42).to_string(), "42");
+
+    let raw: Command = "tp @s ~ ~1 ~".parse().unwrap();
+    assert_eq!(raw, Command::Raw("tp @s ~ ~1 ~".to_string()));
+    assert_eq!(raw.to_string(), "tp @s ~ ~1 ~");
+
+    // Custom variants are also tried if a template only matches the start of the input
+    assert_eq!(
+        parse_command::<Command>("say too many words"),
+        Ok(Command::Raw("say too many words".to_string()))
+    );
+}
@@ -0,0 +1,24 @@
+use command_parser::{parse_command, CommandParse};
+
+#[derive(CommandParse, Debug, PartialEq, Eq)]
+#[command(rename_all = "snake_case")]
+enum Anchor {
+    Eyes,
+    Feet,
+    #[command(rename = "top of head")]
+    TopOfHead,
+    MotionBlockingNoLeaves,
+}
+
+#[derive(CommandParse)]
+enum Empty {}
+
+fn main() {
+    assert_eq!(parse_command::<Anchor>("eyes"), Ok(Anchor::Eyes));
+    assert_eq!(parse_command::<Anchor>("top of head"), Ok(Anchor::TopOfHead));
+    assert_eq!(
+        Anchor::MotionBlockingNoLeaves.to_string(),
+        "motion_blocking_no_leaves"
+    );
+    assert!(parse_command::<Empty>("foo").is_err());
+}
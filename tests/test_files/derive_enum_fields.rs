@@ -0,0 +1,17 @@
+use command_parser::CommandParse;
+
+#[derive(CommandParse)]
+enum Anchor {
+    Eyes,
+    Feet(i32),
+}
+
+#[derive(CommandParse)]
+#[command(rename_all = "snake_case")]
+enum Duplicate {
+    #[command(rename = "foo_bar")]
+    Foo,
+    FooBar,
+}
+
+fn main() {}
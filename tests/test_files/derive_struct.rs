@@ -0,0 +1,21 @@
+use command_parser::{parse_command, CommandParse};
+
+#[derive(CommandParse, Debug, PartialEq, Eq)]
+#[command("$player $scoreboard")]
+struct ScoreboardPlayer {
+    scoreboard: String,
+    player: String,
+}
+
+#[derive(CommandParse, Debug, PartialEq, Eq)]
+#[command("add $0", 1 = true)]
+#[command("remove $0", 1 = false)]
+struct Change(i32, bool);
+
+fn main() {
+    let player: ScoreboardPlayer = parse_command("@s points").unwrap();
+    assert_eq!(player.to_string(), "@s points");
+
+    assert_eq!(parse_command::<Change>("remove 3"), Ok(Change(3, false)));
+    assert_eq!(Change(5, true).to_string(), "add 5");
+}
@@ -0,0 +1,14 @@
+use command_parser::CommandParse;
+
+#[derive(CommandParse)]
+struct NoTemplate {
+    value: i32,
+}
+
+#[derive(CommandParse)]
+#[command("$player $objective")]
+struct UnknownField {
+    player: String,
+}
+
+fn main() {}
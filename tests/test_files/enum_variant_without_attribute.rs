@@ -0,0 +1,12 @@
+use command_parser::parser;
+
+#[parser]
+mod my_module {
+    pub enum Command {
+        #[parse("say $0")]
+        Say(String),
+        Reload,
+    }
+}
+
+fn main() {}
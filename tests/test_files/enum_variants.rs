@@ -0,0 +1,38 @@
+use command_parser::{parse_command, parser};
+
+#[parser]
+mod my_module {
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Command {
+        #[parse("say $0")]
+        Say(String),
+        #[parse("time add $amount", add = true)]
+        #[parse("time remove $amount", add = false)]
+        Time { add: bool, amount: i32 },
+        #[parse("reload")]
+        Reload,
+    }
+
+    #[parse("kill $target")]
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct Kill {
+        pub target: String,
+    }
+}
+
+use my_module::Command;
+
+fn main() {
+    assert_eq!(
+        parse_command::<Command>("say hi"),
+        Ok(Command::Say("hi".to_string()))
+    );
+    let time: Command = "time remove 5".parse().unwrap();
+    assert_eq!(time, Command::Time { add: false, amount: 5 });
+    assert_eq!(time.to_string(), "time remove 5");
+    assert_eq!(Command::Reload.to_string(), "reload");
+    assert_eq!(
+        parse_command::<Command>("kill @e").map(|command| command.to_string()),
+        Ok("kill @e".to_string())
+    );
+}
@@ -0,0 +1,109 @@
+use command_parser::{parser, CommandSet, IntRange};
+
+#[parser]
+pub mod modifiers {
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Modifier {
+        #[parse("as $0")]
+        As(String),
+        #[parse("at $0")]
+        At(String),
+        #[parse("if score $0 $1 matches $2")]
+        IfScore(String, String, command_parser::IntRange),
+    }
+}
+
+#[parser(recursion_limit = 8)]
+mod commands {
+    #[prefix("scoreboard players")]
+    pub mod players {
+        #[derive(Debug, PartialEq, Eq)]
+        pub enum Command {
+            #[parse("reset $0")]
+            Reset(String),
+        }
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Command {
+        #[parse("say $0")]
+        Say(String),
+        #[parse("execute $modifiers* run $command")]
+        Execute {
+            modifiers: Vec<super::modifiers::Modifier>,
+            command: Box<Command>,
+        },
+        #[parse("note $text")]
+        Note { text: Box<String> },
+        #[parse("tags $tags*")]
+        Tags { tags: Vec<String> },
+    }
+}
+
+use commands::Command;
+use modifiers::Modifier;
+
+fn main() {
+    let line = "execute as @a at @s if score @s points matches 3 run say hi";
+    let command: Command = line.parse().unwrap();
+    assert_eq!(
+        command,
+        Command::Execute {
+            modifiers: vec![
+                Modifier::As("@a".to_string()),
+                Modifier::At("@s".to_string()),
+                Modifier::IfScore(
+                    "@s".to_string(),
+                    "points".to_string(),
+                    IntRange {
+                        min: Some(3),
+                        max: Some(3),
+                    },
+                ),
+            ],
+            command: Box::new(Command::Say("hi".to_string())),
+        }
+    );
+    assert_eq!(command.to_string(), line);
+
+    let line = "execute run execute as @a run say hi";
+    let command: Command = line.parse().unwrap();
+    assert_eq!(command.to_string(), line);
+
+    assert!("execute as @a say hi".parse::<Command>().is_err());
+
+    // The limit counts nested `run` redirects, nested command enums do not count
+    let nested = format!("{}say hi", "execute run ".repeat(8));
+    assert!(nested.parse::<Command>().is_ok());
+    let nested = format!("{}scoreboard players reset @s", "execute run ".repeat(8));
+    let command: Command = nested.parse().unwrap();
+    assert_eq!(command.to_string(), nested);
+    // Boxed arguments which are not commands are not redirects
+    let nested = format!("{}note hi", "execute run ".repeat(8));
+    assert!(nested.parse::<Command>().is_ok());
+    let too_deep = format!("{}say hi", "execute run ".repeat(9));
+    assert!(too_deep.parse::<Command>().is_err());
+
+    assert_eq!(
+        Command::usage(),
+        vec![
+            "say <string>",
+            "execute <modifiers...> run <command>",
+            "note <text>",
+            "tags <tags...>",
+            "scoreboard players reset <string>",
+        ]
+    );
+
+    // An empty repeated argument at the end writes no separator
+    let command: Command = "tags".parse().unwrap();
+    assert_eq!(command, Command::Tags { tags: vec![] });
+    assert_eq!(command.to_string(), "tags");
+    assert_eq!(
+        Command::Tags {
+            tags: vec!["a".to_string(), "b".to_string()]
+        }
+        .to_string(),
+        "tags a b"
+    );
+}
@@ -0,0 +1,28 @@
+use command_parser::parser;
+
+#[parser(fallback = Unknown)]
+mod my_module {
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Command {
+        #[parse("say $0")]
+        Say(String),
+    }
+}
+
+use my_module::Command;
+
+fn main() {
+    let lines = ["say hi", "tp @s  ~ ~1 ~ ", "say too many words"];
+    let commands: Vec<Command> = lines.iter().map(|line| line.parse().unwrap()).collect();
+    assert_eq!(commands[0], Command::Say("hi".to_string()));
+    assert_eq!(commands[1], Command::Unknown("tp @s  ~ ~1 ~ ".to_string()));
+    assert_eq!(commands[2], Command::Unknown("say too many words".to_string()));
+    for (line, command) in lines.iter().zip(&commands) {
+        assert_eq!(&command.to_string(), line);
+    }
+
+    // Blank lines are not commands
+    assert!("".parse::<Command>().is_err());
+    assert!("  ".parse::<Command>().is_err());
+    assert!(command_parser::parse_command::<Command>("").is_err());
+}
@@ -0,0 +1,92 @@
+use std::{fmt, net::Ipv4Addr};
+
+use command_parser::{parser, CommandParse};
+
+fn parse_address(value: &str) -> Result<(&str, Ipv4Addr), &str> {
+    let (address, rest) = value.split_once(' ').unwrap_or((value, ""));
+    let address = address.parse().map_err(|_| value)?;
+    Ok((rest, address))
+}
+
+fn write_address(address: &Ipv4Addr, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", address)
+}
+
+#[derive(CommandParse, Debug, PartialEq, Eq)]
+#[command("$name")]
+#[command("$name $amount")]
+pub struct Entry {
+    name: String,
+    #[arg(default = 1)]
+    amount: i32,
+}
+
+#[parser]
+mod commands {
+    use std::net::Ipv4Addr;
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Command {
+        #[parse("connect $address")]
+        #[parse("connect $address $port")]
+        Connect {
+            #[arg(parse_with = super::parse_address, display_with = super::write_address)]
+            address: Ipv4Addr,
+            #[arg(default = 25565)]
+            port: i32,
+        },
+        #[parse("give $0")]
+        Give(super::Entry),
+    }
+
+    #[parse("kick $player")]
+    #[parse("kick $player $reason")]
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct Kick {
+        pub player: String,
+        #[arg(default)]
+        pub reason: String,
+    }
+}
+
+use commands::{Command, Kick};
+
+fn main() {
+    let command: Command = "connect 127.0.0.1".parse().unwrap();
+    assert_eq!(
+        command,
+        Command::Connect {
+            address: Ipv4Addr::LOCALHOST,
+            port: 25565
+        }
+    );
+    assert_eq!(command.to_string(), "connect 127.0.0.1");
+
+    let command: Command = "connect 10.0.0.1 4000".parse().unwrap();
+    assert_eq!(
+        command,
+        Command::Connect {
+            address: Ipv4Addr::new(10, 0, 0, 1),
+            port: 4000
+        }
+    );
+    assert!("connect localhost".parse::<Command>().is_err());
+
+    let command: Command = "kick Steve".parse().unwrap();
+    assert_eq!(
+        command,
+        Command::Kick(Kick {
+            player: "Steve".to_string(),
+            reason: String::new()
+        })
+    );
+
+    let command: Command = "give apple".parse().unwrap();
+    assert_eq!(
+        command,
+        Command::Give(Entry {
+            name: "apple".to_string(),
+            amount: 1
+        })
+    );
+}
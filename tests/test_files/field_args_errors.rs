@@ -0,0 +1,41 @@
+use command_parser::parser;
+
+#[parser]
+mod unknown_argument {
+    pub enum Command {
+        #[parse("say $0")]
+        Say(#[arg(optional)] String),
+    }
+}
+
+#[parser]
+mod missing_path {
+    pub enum Command {
+        #[parse("say $0")]
+        Say(#[arg(parse_with)] String),
+    }
+}
+
+#[parser]
+mod duplicate_default {
+    pub enum Command {
+        #[parse("time $amount")]
+        Time {
+            #[arg(default, default = 1)]
+            amount: i32,
+        },
+    }
+}
+
+#[parser]
+mod default_list {
+    pub enum Command {
+        #[parse("time")]
+        Time {
+            #[arg(default(one))]
+            amount: i32,
+        },
+    }
+}
+
+fn main() {}
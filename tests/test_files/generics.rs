@@ -0,0 +1,63 @@
+use std::marker::PhantomData;
+
+use command_parser::{parser, CommandParse};
+
+#[derive(CommandParse, Debug, PartialEq)]
+#[command("$x $y")]
+pub struct Pair<T> {
+    x: T,
+    y: T,
+}
+
+#[parser]
+mod commands {
+    use std::marker::PhantomData;
+
+    #[derive(Debug, PartialEq)]
+    pub enum Command<'a, P> {
+        #[parse("say $0")]
+        Say(String),
+    }
+
+    #[parse("tp $target $destination")]
+    #[derive(Debug, PartialEq)]
+    pub struct Teleport<P> {
+        pub target: String,
+        pub destination: P,
+    }
+
+    #[parse("reload")]
+    #[derive(Debug, PartialEq)]
+    pub struct Reload<'a> {
+        #[arg(default)]
+        pub marker: PhantomData<&'a ()>,
+    }
+}
+
+use commands::{Command, Teleport};
+
+fn main() {
+    let command: Command<Pair<String>> = "tp @s home spawn".parse().unwrap();
+    assert_eq!(
+        command,
+        Command::Teleport(Teleport {
+            target: "@s".to_string(),
+            destination: Pair {
+                x: "home".to_string(),
+                y: "spawn".to_string()
+            },
+        })
+    );
+    assert_eq!(command.to_string(), "tp @s home spawn");
+
+    let command: Command<String> = "tp @s home".parse().unwrap();
+    assert_eq!(command.to_string(), "tp @s home");
+
+    let command: Command<String> = "reload".parse().unwrap();
+    assert_eq!(
+        command,
+        Command::Reload(commands::Reload {
+            marker: PhantomData
+        })
+    );
+}
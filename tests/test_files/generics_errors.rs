@@ -0,0 +1,17 @@
+use command_parser::parser;
+
+#[parser]
+mod commands {
+    pub enum Command {
+        #[parse("say $0")]
+        Say(String),
+    }
+
+    #[parse("tp $target $destination")]
+    pub struct Teleport<'a, P> {
+        pub target: &'a str,
+        pub destination: P,
+    }
+}
+
+fn main() {}
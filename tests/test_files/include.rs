@@ -0,0 +1,67 @@
+use command_parser::{parser, CommandSet};
+
+#[parser]
+pub mod basic {
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Command {
+        #[parse("say $0")]
+        Say(String),
+        #[parse("tell $target $message")]
+        Tell { target: String, message: String },
+    }
+}
+
+#[parser]
+pub mod redstone {
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Command {
+        #[parse("setblock $0 $1")]
+        Setblock(String, String),
+    }
+}
+
+#[parser(include(super::basic::Command, super::redstone::Command))]
+mod commands {
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Command {
+        #[parse("reload")]
+        Reload,
+    }
+}
+
+use commands::Command;
+
+fn main() {
+    let command: Command = "say hi".parse().unwrap();
+    assert_eq!(
+        command,
+        Command::Basic(basic::Command::Say("hi".to_string()))
+    );
+    assert_eq!(command.to_string(), "say hi");
+
+    let command: Command = "setblock stone here".parse().unwrap();
+    assert_eq!(
+        command,
+        Command::Redstone(redstone::Command::Setblock(
+            "stone".to_string(),
+            "here".to_string()
+        ))
+    );
+    assert_eq!(command.to_string(), "setblock stone here");
+    assert_eq!("reload".parse::<Command>(), Ok(Command::Reload));
+    assert!("fill a b".parse::<Command>().is_err());
+
+    assert_eq!(
+        Command::usage(),
+        vec![
+            "reload",
+            "say <string>",
+            "tell <target> <message>",
+            "setblock <string> <string>",
+        ]
+    );
+    assert_eq!(Command::ROOT_LITERALS, &["reload", "say", "tell", "setblock"]);
+    assert_eq!(Command::completions("s"), vec!["say", "setblock"]);
+    assert_eq!(Command::completions("tell @a "), vec!["<message>"]);
+    assert!(Command::completions("x").is_empty());
+}
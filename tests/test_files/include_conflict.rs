@@ -0,0 +1,24 @@
+use command_parser::parser;
+
+#[parser]
+pub mod basic {
+    pub enum Command {
+        #[parse("say $0")]
+        Say(String),
+    }
+}
+
+#[parser]
+pub mod chat {
+    pub enum Command {
+        #[parse("say $0 $1")]
+        SayTo(String, String),
+    }
+}
+
+#[parser(include(super::basic::Command, super::chat::Command))]
+mod commands {
+    pub enum Command {}
+}
+
+fn main() {}
@@ -0,0 +1,36 @@
+use command_parser::parser;
+
+pub mod vanilla {
+    #[command_parser::parser]
+    pub mod core {
+        pub enum Command {
+            #[parse("say $0")]
+            Say(String),
+        }
+    }
+}
+
+pub mod modded {
+    #[command_parser::parser]
+    pub mod core {
+        pub enum Command {
+            #[parse("warp $0")]
+            Warp(String),
+        }
+    }
+}
+
+#[parser(include(super::vanilla::core::Command, super::modded::core::Command))]
+mod commands {
+    pub enum Command {}
+}
+
+#[parser(include(super::vanilla::core::Command))]
+mod with_variant {
+    pub enum Command {
+        #[parse("reload")]
+        Core,
+    }
+}
+
+fn main() {}
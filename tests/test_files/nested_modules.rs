@@ -0,0 +1,92 @@
+use command_parser::{parser, CommandSet};
+
+#[parser]
+mod commands {
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Command {
+        #[parse("say $0")]
+        Say(String),
+    }
+
+    #[prefix("scoreboard")]
+    pub mod scoreboard {
+        #[derive(Debug, PartialEq, Eq)]
+        pub enum ScoreboardCommand {
+            #[parse("objectives add $0 $1")]
+            AddObjective(String, String),
+        }
+
+        #[prefix("players")]
+        pub mod players {
+            #[derive(Debug, PartialEq, Eq)]
+            pub enum PlayersCommand {
+                #[parse("add $0 $1 $2")]
+                Add(String, String, i32),
+                #[parse("reset $0")]
+                Reset(String),
+            }
+        }
+    }
+
+    #[prefix]
+    pub mod chat {
+        #[derive(Debug, PartialEq, Eq)]
+        pub enum ChatCommand {
+            #[parse("tell $0 $1")]
+            Tell(String, String),
+        }
+    }
+}
+
+use commands::{
+    chat::ChatCommand,
+    scoreboard::{players::PlayersCommand, ScoreboardCommand},
+    Command,
+};
+
+fn main() {
+    let command: Command = "scoreboard players add @s points 5".parse().unwrap();
+    assert_eq!(
+        command,
+        Command::Scoreboard(ScoreboardCommand::Players(PlayersCommand::Add(
+            "@s".to_string(),
+            "points".to_string(),
+            5
+        )))
+    );
+    assert_eq!(command.to_string(), "scoreboard players add @s points 5");
+
+    let command: Command = "scoreboard objectives add points dummy".parse().unwrap();
+    assert_eq!(
+        command,
+        Command::Scoreboard(ScoreboardCommand::AddObjective(
+            "points".to_string(),
+            "dummy".to_string()
+        ))
+    );
+    assert_eq!(command.to_string(), "scoreboard objectives add points dummy");
+
+    let command: Command = "tell @a hi".parse().unwrap();
+    assert_eq!(
+        command,
+        Command::Chat(ChatCommand::Tell("@a".to_string(), "hi".to_string()))
+    );
+    assert_eq!(command.to_string(), "tell @a hi");
+
+    let players: PlayersCommand = "reset @s".parse().unwrap();
+    assert_eq!(players, PlayersCommand::Reset("@s".to_string()));
+    assert!("scoreboard players".parse::<Command>().is_err());
+    assert!("scoreboard players reset".parse::<Command>().is_err());
+
+    assert_eq!(
+        Command::usage(),
+        vec![
+            "say <string>",
+            "scoreboard objectives add <string> <string>",
+            "scoreboard players add <string> <string> <i32>",
+            "scoreboard players reset <string>",
+            "tell <string> <string>",
+        ]
+    );
+    assert_eq!(Command::completions("scoreboard p"), vec!["players"]);
+}
@@ -0,0 +1,60 @@
+use command_parser::{parse_command, parser};
+
+mod reexport {
+    pub use command_parser::*;
+}
+
+#[parser(target = Command, crate = crate::reexport, case_insensitive)]
+mod commands {
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Helper {
+        A,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Command {
+        #[parse("Say $0")]
+        Say(String),
+    }
+}
+
+#[parser(separator = '.')]
+mod dotted {
+    #[prefix("scoreboard")]
+    pub mod scoreboard {
+        #[derive(Debug, PartialEq, Eq)]
+        pub enum Scoreboard {
+            #[parse("reset $0 $1")]
+            Reset(String, String),
+        }
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Path {
+        #[parse("config value $0")]
+        Value(i32),
+        #[parse("set $0 $1")]
+        Set(String, String),
+    }
+}
+
+fn main() {
+    assert_eq!(commands::Helper::A, commands::Helper::A);
+    assert_eq!(
+        parse_command::<commands::Command>("SAY hi"),
+        Ok(commands::Command::Say("hi".to_string()))
+    );
+    assert_eq!(commands::Command::Say("hi".to_string()).to_string(), "Say hi");
+
+    let value: dotted::Path = "config.value.5".parse().unwrap();
+    assert_eq!(value, dotted::Path::Value(5));
+    assert_eq!(value.to_string(), "config.value.5");
+
+    // Arguments are separated by the separator as well
+    let set: dotted::Path = "set.a.b".parse().unwrap();
+    assert_eq!(set, dotted::Path::Set("a".to_string(), "b".to_string()));
+    assert_eq!(set.to_string(), "set.a.b");
+    assert!("set.a b.c".parse::<dotted::Path>().is_err());
+    let reset: dotted::Path = "scoreboard.reset.a.b".parse().unwrap();
+    assert_eq!(reset.to_string(), "scoreboard.reset.a.b");
+}
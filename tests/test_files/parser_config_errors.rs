@@ -0,0 +1,31 @@
+use command_parser::parser;
+
+#[parser(target = Missing)]
+mod missing {
+    pub enum Command {}
+}
+
+#[parser(separator = ".")]
+mod bad_separator {
+    pub enum Command {}
+}
+
+#[parser(separator = '.')]
+mod dotted_repeat {
+    pub enum Command {
+        #[parse("tags $tags*")]
+        Tags { tags: Vec<String> },
+    }
+}
+
+#[parser(recursion_limit = 0)]
+mod no_recursion {
+    pub enum Command {}
+}
+
+#[parser(verbose)]
+mod unknown {
+    pub enum Command {}
+}
+
+fn main() {}
@@ -0,0 +1,43 @@
+use command_parser::parser;
+
+#[parser]
+mod unbound_field {
+    pub enum Command {
+        #[parse("time add $amount")]
+        Time { add: bool, amount: i32 },
+    }
+}
+
+#[parser]
+mod unknown_kwarg {
+    pub enum Command {
+        #[parse("time add $amount", add = true, remove = false)]
+        Time { add: bool, amount: i32 },
+    }
+}
+
+#[parser]
+mod parsed_twice {
+    pub enum Command {
+        #[parse("tp $0 $0")]
+        Teleport(String),
+    }
+}
+
+#[parser]
+mod parsed_and_set {
+    pub enum Command {
+        #[parse("say $0", 0 = String::new())]
+        Say(String),
+    }
+}
+
+#[parser]
+mod set_twice {
+    pub enum Command {
+        #[parse("reload", force = true, force = false)]
+        Reload { force: bool },
+    }
+}
+
+fn main() {}
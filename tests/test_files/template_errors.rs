@@ -0,0 +1,21 @@
+use command_parser::parser;
+
+#[parser]
+mod commands {
+    pub enum Command {
+        #[parse("tp $target $destination", facing = true)]
+        Teleport { target: String, location: String },
+        #[parse("say $message $message")]
+        Say(String),
+        #[parse("kill $target")]
+        Kill { target: String },
+    }
+
+    #[parse("give $player $item-stack")]
+    pub struct Give {
+        pub player: String,
+        pub item: String,
+    }
+}
+
+fn main() {}